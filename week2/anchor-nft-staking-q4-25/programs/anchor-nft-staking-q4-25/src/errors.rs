@@ -22,4 +22,40 @@ pub enum StakeError {
     NotOwner,
     #[msg("No reward points in the user account")]
     NoPoints,
+    #[msg("Not Admin")]
+    NotAdmin,
+    #[msg("Staking Is Paused")]
+    StakingPaused,
+    #[msg("Reward Budget Exhausted")]
+    RewardBudgetExhausted,
+    #[msg("Invalid Epoch Duration")]
+    InvalidEpochDuration,
+    #[msg("Remaining Accounts Must Be Asset, Collection, Stake Account Triples")]
+    InvalidBatch,
+    #[msg("Batch Too Large")]
+    BatchTooLarge,
+    #[msg("Invalid Stake Account")]
+    InvalidStakeAccount,
+    #[msg("Invalid Lock Tier")]
+    InvalidLockTier,
+    #[msg("Invalid Lock Tiers")]
+    InvalidLockTiers,
+    #[msg("Invalid Penalty")]
+    InvalidPenalty,
+    #[msg("Not Enough Points")]
+    InsufficientPoints,
+    #[msg("Item Sold Out")]
+    ItemSoldOut,
+    #[msg("Missing Asset Accounts")]
+    MissingAssetAccounts,
+    #[msg("Position Is Held Through A Receipt")]
+    ReceiptRequired,
+    #[msg("Not Receipt Holder")]
+    NotReceiptHolder,
+    #[msg("Assets Still Staked")]
+    AssetsStillStaked,
+    #[msg("Arithmetic Overflow")]
+    Overflow,
+    #[msg("Item Name Too Long")]
+    ItemNameTooLong,
 }
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::{
+    errors::StakeError,
+    state::{LegacyCollection, StakeConfig},
+};
+
+#[derive(Accounts)]
+pub struct AllowLegacyCollection<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        has_one = admin @ StakeError::NotAdmin,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    pub collection_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = LegacyCollection::DISCRIMINATOR.len() + LegacyCollection::INIT_SPACE,
+        seeds = [b"legacy_collection", config.key().as_ref(), collection_mint.key().as_ref()],
+        bump,
+    )]
+    pub legacy_collection: Account<'info, LegacyCollection>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AllowLegacyCollection<'info> {
+    pub fn allow_legacy_collection(&mut self, bumps: &AllowLegacyCollectionBumps) -> Result<()> {
+        self.legacy_collection.set_inner(LegacyCollection {
+            collection_mint: self.collection_mint.key(),
+            bump: bumps.legacy_collection,
+        });
+
+        Ok(())
+    }
+}
@@ -5,7 +5,7 @@ use anchor_spl::{
 };
 
 use crate::errors::StakeError;
-use crate::state::{StakeConfig, UserAccount};
+use crate::state::{PoolStats, StakeConfig, UserAccount};
 
 #[derive(Accounts)]
 pub struct Claim<'info> {
@@ -42,6 +42,13 @@ pub struct Claim<'info> {
     )]
     pub user_account: Account<'info, UserAccount>,
 
+    #[account(
+        mut,
+        seeds = [b"stats".as_ref(), config.key().as_ref()],
+        bump = pool_stats.bump,
+    )]
+    pub pool_stats: Account<'info, PoolStats>,
+
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Program<'info, Token>,
@@ -51,7 +58,12 @@ impl<'info> Claim<'info> {
     pub fn claim(&mut self) -> Result<()> {
         require!(self.user_account.points > 0, StakeError::NoPoints);
 
-        let amount = self.user_account.points as f64 * 10e6;
+        // Pay out as many points as the remaining budget covers, keep the rest
+        let (points, amount) = self
+            .config
+            .claimable(self.user_account.points, Clock::get()?.unix_timestamp);
+        require!(points > 0, StakeError::RewardBudgetExhausted);
+
         let mint_to_accounts = MintTo {
             mint: self.reward_mint.to_account_info(),
             to: self.user_reward_ata.to_account_info(),
@@ -65,10 +77,12 @@ impl<'info> Claim<'info> {
                 mint_to_accounts,
                 signer_seeds,
             ),
-            amount as u64,
+            amount,
         )?;
 
-        self.user_account.points = 0;
+        self.config.rewards_minted += amount;
+        self.pool_stats.record_rewards(amount)?;
+        self.user_account.points -= points;
 
         Ok(())
     }
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{mint_to, Mint, MintTo, Token, TokenAccount};
+
+use crate::{
+    errors::StakeError,
+    state::{PoolStats, StakeConfig, UserAccount},
+};
+
+#[derive(Accounts)]
+pub struct CloseUser<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"user".as_ref(), user.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.amount_staked == 0 @ StakeError::AssetsStillStaked,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"rewards".as_ref(), config.key().as_ref()],
+        bump = config.rewards_bump,
+    )]
+    pub reward_mint: Account<'info, Mint>,
+
+    // Remaining points are claimed into this account when given, forfeited otherwise
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = user,
+    )]
+    pub user_reward_ata: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"stats".as_ref(), config.key().as_ref()],
+        bump = pool_stats.bump,
+    )]
+    pub pool_stats: Account<'info, PoolStats>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CloseUser<'info> {
+    pub fn close_user(&mut self) -> Result<()> {
+        self.pool_stats.remove_from_leaderboard(self.user.key());
+
+        let Some(user_reward_ata) = &self.user_reward_ata else {
+            return Ok(());
+        };
+
+        // Points the budget can no longer cover are forfeited with the account
+        let (points, amount) = self
+            .config
+            .claimable(self.user_account.points, Clock::get()?.unix_timestamp);
+        if points == 0 {
+            return Ok(());
+        }
+
+        let signer_seeds: &[&[&[u8]]] = &[&[b"config".as_ref(), &[self.config.bump]]];
+
+        mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: self.reward_mint.to_account_info(),
+                    to: user_reward_ata.to_account_info(),
+                    authority: self.config.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+
+        self.config.rewards_minted += amount;
+        self.pool_stats.record_rewards(amount)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::StakeError,
+    state::{RedeemItem, StakeConfig},
+};
+
+#[derive(Accounts)]
+pub struct DelistItem<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        has_one = admin @ StakeError::NotAdmin,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"item", config.key().as_ref(), item.id.to_le_bytes().as_ref()],
+        bump = item.bump,
+    )]
+    pub item: Account<'info, RedeemItem>,
+}
+
+impl<'info> DelistItem<'info> {
+    // Redemption records stay on-chain after the item is delisted
+    pub fn delist_item(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
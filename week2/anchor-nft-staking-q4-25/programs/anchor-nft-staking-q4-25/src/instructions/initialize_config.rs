@@ -1,7 +1,13 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token};
 
-use crate::state::StakeConfig;
+use crate::{
+    errors::StakeError,
+    state::{
+        LockTier, PoolStats, StakeConfig, DEFAULT_EARLY_UNSTAKE_PENALTY_BPS,
+        DEFAULT_REWARD_PER_POINT,
+    },
+};
 
 #[derive(Accounts)]
 pub struct InitializeConfig<'info> {
@@ -27,6 +33,15 @@ pub struct InitializeConfig<'info> {
     )]
     pub reward_mint: Account<'info, Mint>,
 
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"stats".as_ref(), config.key().as_ref()],
+        bump,
+        space = PoolStats::DISCRIMINATOR.len() + PoolStats::INIT_SPACE,
+    )]
+    pub pool_stats: Account<'info, PoolStats>,
+
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
@@ -37,16 +52,44 @@ impl<'info> InitializeConfig<'info> {
         points_per_stake: u8,
         max_stake: u8,
         freeze_period: u32,
+        reward_budget: u64,
+        epoch_duration: i64,
         bumps: &InitializeConfigBumps,
     ) -> Result<()> {
+        require!(epoch_duration > 0, StakeError::InvalidEpochDuration);
+
+        let now = Clock::get()?.unix_timestamp;
         self.config.set_inner(StakeConfig {
+            admin: self.admin.key(),
             points_per_stake,
             max_stake,
             freeze_period,
+            paused: false,
+            points_index: 0,
+            last_update: now,
+            reward_budget,
+            rewards_minted: 0,
+            emission_start: now,
+            epoch_duration,
+            base_reward_per_point: DEFAULT_REWARD_PER_POINT,
+            lock_tiers: vec![LockTier {
+                lock_days: 0,
+                multiplier_bps: 10_000,
+            }],
+            early_unstake_penalty_bps: DEFAULT_EARLY_UNSTAKE_PENALTY_BPS,
             rewards_bump: bumps.reward_mint,
             bump: bumps.config,
         });
 
+        self.pool_stats.set_inner(PoolStats {
+            total_staked: 0,
+            total_points_issued: 0,
+            total_rewards_minted: 0,
+            active_stakers: 0,
+            leaderboard: Vec::new(),
+            bump: bumps.pool_stats,
+        });
+
         Ok(())
     }
 }
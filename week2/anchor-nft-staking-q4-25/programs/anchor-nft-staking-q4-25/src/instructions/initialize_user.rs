@@ -23,6 +23,7 @@ impl<'info> Initialize<'info> {
     pub fn initialize_user_account(&mut self, bumps: &InitializeBumps) -> Result<()> {
         self.user_account.set_inner(UserAccount {
             points: 0,
+            lifetime_points: 0,
             amount_staked: 0,
             bump: bumps.user_account,
         });
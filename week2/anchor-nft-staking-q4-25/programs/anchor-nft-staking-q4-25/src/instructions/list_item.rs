@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::StakeError,
+    state::{ItemKind, RedeemItem, StakeConfig, MAX_ITEM_NAME_LEN},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ListItemArgs {
+    pub id: u64,
+    pub kind: ItemKind,
+    pub name: String,
+    pub cost: u32,
+    pub supply: u32,
+}
+
+#[derive(Accounts)]
+#[instruction(args: ListItemArgs)]
+pub struct ListItem<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        has_one = admin @ StakeError::NotAdmin,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = RedeemItem::DISCRIMINATOR.len() + RedeemItem::INIT_SPACE,
+        seeds = [b"item", config.key().as_ref(), args.id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub item: Account<'info, RedeemItem>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ListItem<'info> {
+    pub fn list_item(&mut self, args: ListItemArgs, bumps: &ListItemBumps) -> Result<()> {
+        require!(
+            args.name.len() <= MAX_ITEM_NAME_LEN,
+            StakeError::ItemNameTooLong
+        );
+
+        self.item.set_inner(RedeemItem {
+            id: args.id,
+            kind: args.kind,
+            name: args.name,
+            cost: args.cost,
+            supply: args.supply,
+            redeemed: 0,
+            bump: bumps.item,
+        });
+
+        Ok(())
+    }
+}
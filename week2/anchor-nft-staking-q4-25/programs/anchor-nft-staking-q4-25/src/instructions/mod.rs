@@ -1,9 +1,15 @@
 pub mod initialize_user;
 pub use initialize_user::*;
 
+pub mod close_user;
+pub use close_user::*;
+
 pub mod initialize_config;
 pub use initialize_config::*;
 
+pub mod update_config;
+pub use update_config::*;
+
 pub mod create_collection;
 pub use create_collection::*;
 
@@ -16,5 +22,38 @@ pub use stake::*;
 pub mod unstake;
 pub use unstake::*;
 
+pub mod stake_many;
+pub use stake_many::*;
+
+pub mod unstake_many;
+pub use unstake_many::*;
+
+pub mod stake_with_receipt;
+pub use stake_with_receipt::*;
+
+pub mod unstake_with_receipt;
+pub use unstake_with_receipt::*;
+
+pub mod allow_legacy_collection;
+pub use allow_legacy_collection::*;
+
+pub mod remove_legacy_collection;
+pub use remove_legacy_collection::*;
+
+pub mod stake_legacy;
+pub use stake_legacy::*;
+
+pub mod unstake_legacy;
+pub use unstake_legacy::*;
+
 pub mod claim;
 pub use claim::*;
+
+pub mod list_item;
+pub use list_item::*;
+
+pub mod delist_item;
+pub use delist_item::*;
+
+pub mod redeem;
+pub use redeem::*;
@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+use mpl_core::{instructions::CreateV2CpiBuilder, ID as CORE_PROGRAM_ID};
+
+use crate::{
+    errors::StakeError,
+    state::{CollectionInfo, ItemKind, RedeemItem, Redemption, StakeConfig, UserAccount},
+};
+
+#[derive(Accounts)]
+pub struct Redeem<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user".as_ref(), user.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"item", config.key().as_ref(), item.id.to_le_bytes().as_ref()],
+        bump = item.bump,
+    )]
+    pub item: Account<'info, RedeemItem>,
+
+    // Numbered by the item's redemption count, so a user can redeem an item more than once
+    #[account(
+        init,
+        payer = user,
+        space = Redemption::DISCRIMINATOR.len() + Redemption::INIT_SPACE,
+        seeds = [b"redemption", item.key().as_ref(), item.redeemed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub redemption: Account<'info, Redemption>,
+
+    // Only required for ItemKind::CoreAsset
+    #[account(
+        mut,
+        constraint = asset.data_is_empty() @ StakeError::AssetAlreadyInitialized
+    )]
+    pub asset: Option<Signer<'info>>,
+
+    #[account(
+        mut,
+        constraint = collection.owner == &CORE_PROGRAM_ID @ StakeError::InvalidCollection,
+    )]
+    /// CHECK: Verified by mpl-core
+    pub collection: Option<UncheckedAccount<'info>>,
+
+    pub collection_info: Option<Account<'info, CollectionInfo>>,
+
+    #[account(address = CORE_PROGRAM_ID)]
+    /// CHECK: Verified by address constraint
+    pub core_program: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Redeem<'info> {
+    pub fn redeem(&mut self, bumps: &RedeemBumps) -> Result<()> {
+        require!(
+            self.item.redeemed < self.item.supply,
+            StakeError::ItemSoldOut
+        );
+        require!(
+            self.user_account.points >= self.item.cost,
+            StakeError::InsufficientPoints
+        );
+
+        let asset = match self.item.kind {
+            ItemKind::WhitelistSpot => None,
+            ItemKind::CoreAsset { collection } => Some(self.mint_asset(collection)?),
+        };
+
+        self.user_account.points -= self.item.cost;
+        self.item.redeemed += 1;
+
+        self.redemption.set_inner(Redemption {
+            user: self.user.key(),
+            item: self.item.key(),
+            points_spent: self.item.cost,
+            asset,
+            redeemed_at: Clock::get()?.unix_timestamp,
+            bump: bumps.redemption,
+        });
+
+        Ok(())
+    }
+
+    fn mint_asset(&self, collection_key: Pubkey) -> Result<Pubkey> {
+        let (Some(asset), Some(collection), Some(collection_info), Some(core_program)) = (
+            &self.asset,
+            &self.collection,
+            &self.collection_info,
+            &self.core_program,
+        ) else {
+            return err!(StakeError::MissingAssetAccounts);
+        };
+        require_keys_eq!(
+            collection.key(),
+            collection_key,
+            StakeError::InvalidCollection
+        );
+        require_keys_eq!(
+            collection_info.collection,
+            collection_key,
+            StakeError::InvalidCollection
+        );
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"collection_info",
+            &collection_key.to_bytes(),
+            &[collection_info.bump],
+        ]];
+
+        CreateV2CpiBuilder::new(&core_program.to_account_info())
+            .asset(&asset.to_account_info())
+            .collection(Some(&collection.to_account_info()))
+            .authority(Some(&collection_info.to_account_info()))
+            .payer(&self.user.to_account_info())
+            .owner(Some(&self.user.to_account_info()))
+            .update_authority(None)
+            .system_program(&self.system_program.to_account_info())
+            .name(collection_info.nft_name.clone())
+            .uri(collection_info.nft_uri.clone())
+            .plugins(vec![])
+            .external_plugin_adapters(vec![])
+            .invoke_signed(signer_seeds)?;
+
+        Ok(asset.key())
+    }
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::StakeError,
+    state::{LegacyCollection, StakeConfig},
+};
+
+#[derive(Accounts)]
+pub struct RemoveLegacyCollection<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        has_one = admin @ StakeError::NotAdmin,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [
+            b"legacy_collection",
+            config.key().as_ref(),
+            legacy_collection.collection_mint.as_ref(),
+        ],
+        bump = legacy_collection.bump,
+    )]
+    pub legacy_collection: Account<'info, LegacyCollection>,
+}
+
+impl<'info> RemoveLegacyCollection<'info> {
+    // NFTs already staked from the collection can still be unstaked
+    pub fn remove_legacy_collection(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
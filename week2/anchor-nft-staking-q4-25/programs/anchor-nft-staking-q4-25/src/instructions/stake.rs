@@ -7,7 +7,7 @@ use mpl_core::{
 
 use crate::{
     errors::StakeError,
-    state::{StakeAccount, StakeConfig, UserAccount},
+    state::{PoolStats, StakeAccount, StakeConfig, UserAccount},
 };
 
 #[derive(Accounts)]
@@ -54,6 +54,13 @@ pub struct Stake<'info> {
     )]
     pub user_account: Account<'info, UserAccount>,
 
+    #[account(
+        mut,
+        seeds = [b"stats".as_ref(), config.key().as_ref()],
+        bump = pool_stats.bump,
+    )]
+    pub pool_stats: Account<'info, PoolStats>,
+
     #[account(address = CORE_PROGRAM_ID)]
     /// CHECK: verified by address constraint
     pub core_program: UncheckedAccount<'info>,
@@ -62,11 +69,17 @@ pub struct Stake<'info> {
 }
 
 impl<'info> Stake<'info> {
-    pub fn stake(&mut self, bumps: &StakeBumps) -> Result<()> {
+    pub fn stake(&mut self, tier: u8, bumps: &StakeBumps) -> Result<()> {
+        require!(!self.config.paused, StakeError::StakingPaused);
         require!(
             self.user_account.amount_staked < self.config.max_stake,
             StakeError::MaxStakeReached
         );
+        let lock_tier = *self
+            .config
+            .lock_tiers
+            .get(tier as usize)
+            .ok_or(StakeError::InvalidLockTier)?;
 
         AddPluginV1CpiBuilder::new(&self.core_program.to_account_info())
             .asset(&self.asset.to_account_info())
@@ -80,13 +93,22 @@ impl<'info> Stake<'info> {
             })
             .invoke()?;
 
+        let now = Clock::get()?.unix_timestamp;
+        self.config.accrue(now)?;
+
         self.stake_account.set_inner(StakeAccount {
             owner: *self.user.key,
             mint: self.asset.key(),
-            staked_at: Clock::get()?.unix_timestamp,
+            staked_at: now,
+            points_index: self.config.points_index,
+            lock_days: lock_tier.lock_days,
+            multiplier_bps: lock_tier.multiplier_bps,
+            receipt_mint: None,
             bump: bumps.stake_account,
         });
 
+        self.pool_stats
+            .add_staked(1, self.user_account.amount_staked == 0)?;
         self.user_account.amount_staked += 1;
 
         Ok(())
@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{
+        freeze_delegated_account, FreezeDelegatedAccount, MasterEditionAccount, Metadata,
+        MetadataAccount,
+    },
+    token::{approve, Approve, Mint, Token, TokenAccount},
+};
+
+use crate::{
+    errors::StakeError,
+    state::{LegacyCollection, PoolStats, StakeAccount, StakeConfig, UserAccount},
+};
+
+// Stakes a Token Metadata NFT from an allowed collection by freezing it in the user's token account
+#[derive(Accounts)]
+pub struct StakeLegacy<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    pub collection_mint: Account<'info, Mint>,
+
+    // Only collections the admin has allowed, or anyone could verify their own
+    #[account(
+        seeds = [b"legacy_collection", config.key().as_ref(), collection_mint.key().as_ref()],
+        bump = legacy_collection.bump,
+    )]
+    pub legacy_collection: Account<'info, LegacyCollection>,
+
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = user,
+        constraint = user_nft_ata.amount == 1 @ StakeError::InvalidAsset,
+    )]
+    pub user_nft_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"metadata", metadata_program.key().as_ref(), nft_mint.key().as_ref()],
+        seeds::program = metadata_program.key(),
+        bump,
+        constraint = metadata
+            .collection
+            .as_ref()
+            .is_some_and(|collection| collection.verified && collection.key == collection_mint.key())
+            @ StakeError::InvalidCollection,
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    #[account(
+        seeds = [b"metadata", metadata_program.key().as_ref(), nft_mint.key().as_ref(), b"edition"],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub edition: Account<'info, MasterEditionAccount>,
+
+    #[account(
+        init,
+        payer = user,
+        space = StakeAccount::DISCRIMINATOR.len() + StakeAccount::INIT_SPACE,
+        seeds = [b"stake", config.key().as_ref(), nft_mint.key().as_ref()],
+        bump,
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user".as_ref(), user.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stats".as_ref(), config.key().as_ref()],
+        bump = pool_stats.bump,
+    )]
+    pub pool_stats: Account<'info, PoolStats>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> StakeLegacy<'info> {
+    pub fn stake_legacy(&mut self, tier: u8, bumps: &StakeLegacyBumps) -> Result<()> {
+        require!(!self.config.paused, StakeError::StakingPaused);
+        require!(
+            self.user_account.amount_staked < self.config.max_stake,
+            StakeError::MaxStakeReached
+        );
+        let lock_tier = *self
+            .config
+            .lock_tiers
+            .get(tier as usize)
+            .ok_or(StakeError::InvalidLockTier)?;
+
+        approve(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Approve {
+                    to: self.user_nft_ata.to_account_info(),
+                    delegate: self.stake_account.to_account_info(),
+                    authority: self.user.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"stake",
+            &self.config.key().to_bytes(),
+            &self.nft_mint.key().to_bytes(),
+            &[bumps.stake_account],
+        ]];
+
+        freeze_delegated_account(CpiContext::new_with_signer(
+            self.metadata_program.to_account_info(),
+            FreezeDelegatedAccount {
+                metadata: self.metadata_program.to_account_info(),
+                delegate: self.stake_account.to_account_info(),
+                token_account: self.user_nft_ata.to_account_info(),
+                edition: self.edition.to_account_info(),
+                mint: self.nft_mint.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        let now = Clock::get()?.unix_timestamp;
+        self.config.accrue(now)?;
+
+        self.stake_account.set_inner(StakeAccount {
+            owner: *self.user.key,
+            mint: self.nft_mint.key(),
+            staked_at: now,
+            points_index: self.config.points_index,
+            lock_days: lock_tier.lock_days,
+            multiplier_bps: lock_tier.multiplier_bps,
+            receipt_mint: None,
+            bump: bumps.stake_account,
+        });
+
+        self.pool_stats
+            .add_staked(1, self.user_account.amount_staked == 0)?;
+        self.user_account.amount_staked += 1;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{
+        allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+    },
+};
+use mpl_core::{
+    instructions::AddPluginV1CpiBuilder,
+    types::{FreezeDelegate, Plugin, PluginAuthority},
+    ID as CORE_PROGRAM_ID,
+};
+
+use crate::{
+    errors::StakeError,
+    state::{PoolStats, StakeAccount, StakeConfig, UserAccount},
+};
+
+// Every asset takes an (asset, collection, stake_account) triple of remaining accounts.
+// Eight triples plus the fixed accounts stay under the legacy transaction size limit,
+// and unstaking them (two plugin CPIs each) fits in a 400k CU budget.
+pub const MAX_BATCH_SIZE: usize = 8;
+
+pub fn batch_size(remaining_accounts: &[AccountInfo]) -> Result<usize> {
+    require!(
+        !remaining_accounts.is_empty() && remaining_accounts.len() % 3 == 0,
+        StakeError::InvalidBatch
+    );
+    let count = remaining_accounts.len() / 3;
+    require!(count <= MAX_BATCH_SIZE, StakeError::BatchTooLarge);
+
+    Ok(count)
+}
+
+// Same steps as Anchor's `init`: the stake PDA is derivable, so anyone can send it lamports
+// first, and create_account would then fail and block staking the asset for good
+pub fn create_stake_account<'info>(
+    payer: &AccountInfo<'info>,
+    stake_account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    space: usize,
+) -> Result<()> {
+    let required = Rent::get()?.minimum_balance(space);
+    let current = stake_account.lamports();
+
+    if current == 0 {
+        return create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                CreateAccount {
+                    from: payer.clone(),
+                    to: stake_account.clone(),
+                },
+                signer_seeds,
+            ),
+            required,
+            space as u64,
+            &crate::ID,
+        );
+    }
+
+    if required > current {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: stake_account.clone(),
+                },
+            ),
+            required - current,
+        )?;
+    }
+    allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Allocate {
+                account_to_allocate: stake_account.clone(),
+            },
+            signer_seeds,
+        ),
+        space as u64,
+    )?;
+    assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Assign {
+                account_to_assign: stake_account.clone(),
+            },
+            signer_seeds,
+        ),
+        &crate::ID,
+    )
+}
+
+#[derive(Accounts)]
+pub struct StakeMany<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user".as_ref(), user.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stats".as_ref(), config.key().as_ref()],
+        bump = pool_stats.bump,
+    )]
+    pub pool_stats: Account<'info, PoolStats>,
+
+    #[account(address = CORE_PROGRAM_ID)]
+    /// CHECK: verified by address constraint
+    pub core_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> StakeMany<'info> {
+    pub fn stake_many(
+        &mut self,
+        tier: u8,
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(!self.config.paused, StakeError::StakingPaused);
+        let lock_tier = *self
+            .config
+            .lock_tiers
+            .get(tier as usize)
+            .ok_or(StakeError::InvalidLockTier)?;
+
+        let count = batch_size(remaining_accounts)?;
+        require!(
+            self.user_account.amount_staked as usize + count <= self.config.max_stake as usize,
+            StakeError::MaxStakeReached
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        self.config.accrue(now)?;
+
+        let config_key = self.config.key();
+        let space = StakeAccount::DISCRIMINATOR.len() + StakeAccount::INIT_SPACE;
+
+        for accounts in remaining_accounts.chunks(3) {
+            let (asset, collection, stake_account) = (&accounts[0], &accounts[1], &accounts[2]);
+
+            require!(
+                asset.owner == &CORE_PROGRAM_ID && !asset.data_is_empty(),
+                StakeError::InvalidAsset
+            );
+            require!(
+                collection.owner == &CORE_PROGRAM_ID && !collection.data_is_empty(),
+                StakeError::InvalidCollection
+            );
+
+            let (stake_key, bump) = Pubkey::find_program_address(
+                &[b"stake", config_key.as_ref(), asset.key.as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(
+                stake_account.key(),
+                stake_key,
+                StakeError::InvalidStakeAccount
+            );
+
+            let signer_seeds: &[&[&[u8]]] =
+                &[&[b"stake", config_key.as_ref(), asset.key.as_ref(), &[bump]]];
+
+            create_stake_account(
+                &self.user.to_account_info(),
+                stake_account,
+                &self.system_program.to_account_info(),
+                signer_seeds,
+                space,
+            )?;
+
+            AddPluginV1CpiBuilder::new(&self.core_program.to_account_info())
+                .asset(asset)
+                .collection(Some(collection))
+                .payer(&self.user.to_account_info())
+                .authority(None)
+                .system_program(&self.system_program.to_account_info())
+                .plugin(Plugin::FreezeDelegate(FreezeDelegate { frozen: true }))
+                .init_authority(PluginAuthority::Address { address: stake_key })
+                .invoke()?;
+
+            StakeAccount {
+                owner: *self.user.key,
+                mint: asset.key(),
+                staked_at: now,
+                points_index: self.config.points_index,
+                lock_days: lock_tier.lock_days,
+                multiplier_bps: lock_tier.multiplier_bps,
+                receipt_mint: None,
+                bump,
+            }
+            .try_serialize(&mut &mut stake_account.try_borrow_mut_data()?[..])?;
+        }
+
+        self.pool_stats
+            .add_staked(count as u64, self.user_account.amount_staked == 0)?;
+        self.user_account.amount_staked += count as u8;
+
+        Ok(())
+    }
+}
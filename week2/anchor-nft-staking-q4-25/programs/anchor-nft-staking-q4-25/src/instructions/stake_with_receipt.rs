@@ -0,0 +1,174 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{
+        mint_to, set_authority, spl_token::instruction::AuthorityType, Mint, MintTo, SetAuthority,
+        Token, TokenAccount,
+    },
+};
+use mpl_core::{instructions::TransferV1CpiBuilder, ID as CORE_PROGRAM_ID};
+
+use crate::{
+    errors::StakeError,
+    state::{PoolStats, StakeAccount, StakeConfig, UserAccount},
+};
+
+// The asset is escrowed with the stake account rather than frozen in place, so it can
+// follow the receipt to whoever holds it at unstake time
+#[derive(Accounts)]
+pub struct StakeWithReceipt<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = asset.owner == &CORE_PROGRAM_ID,
+        constraint = !asset.data_is_empty(),
+    )]
+    /// CHECK: verified by CORE_PROGRAM as well
+    pub asset: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = collection.owner == &CORE_PROGRAM_ID,
+        constraint = !collection.data_is_empty()
+    )]
+    /// CHECK: verified by CORE_PROGRAM as well
+    pub collection: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = StakeAccount::DISCRIMINATOR.len() + StakeAccount::INIT_SPACE,
+        seeds = [b"stake", config.key().as_ref(), asset.key().as_ref()],
+        bump,
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"receipt".as_ref(), stake_account.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = stake_account,
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_receipt_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user".as_ref(), user.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stats".as_ref(), config.key().as_ref()],
+        bump = pool_stats.bump,
+    )]
+    pub pool_stats: Account<'info, PoolStats>,
+
+    #[account(address = CORE_PROGRAM_ID)]
+    /// CHECK: verified by address constraint
+    pub core_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> StakeWithReceipt<'info> {
+    pub fn stake_with_receipt(&mut self, tier: u8, bumps: &StakeWithReceiptBumps) -> Result<()> {
+        require!(!self.config.paused, StakeError::StakingPaused);
+        let lock_tier = *self
+            .config
+            .lock_tiers
+            .get(tier as usize)
+            .ok_or(StakeError::InvalidLockTier)?;
+        require!(
+            self.user_account.amount_staked < self.config.max_stake,
+            StakeError::MaxStakeReached
+        );
+
+        TransferV1CpiBuilder::new(&self.core_program.to_account_info())
+            .asset(&self.asset.to_account_info())
+            .collection(Some(&self.collection.to_account_info()))
+            .payer(&self.user.to_account_info())
+            .authority(None)
+            .new_owner(&self.stake_account.to_account_info())
+            .system_program(Some(&self.system_program.to_account_info()))
+            .invoke()?;
+
+        let now = Clock::get()?.unix_timestamp;
+        self.config.accrue(now)?;
+
+        self.stake_account.set_inner(StakeAccount {
+            owner: *self.user.key,
+            mint: self.asset.key(),
+            staked_at: now,
+            points_index: self.config.points_index,
+            lock_days: lock_tier.lock_days,
+            multiplier_bps: lock_tier.multiplier_bps,
+            receipt_mint: Some(self.receipt_mint.key()),
+            bump: bumps.stake_account,
+        });
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"stake",
+            &self.config.key().to_bytes(),
+            &self.asset.key.to_bytes(),
+            &[bumps.stake_account],
+        ]];
+
+        mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                MintTo {
+                    mint: self.receipt_mint.to_account_info(),
+                    to: self.user_receipt_ata.to_account_info(),
+                    authority: self.stake_account.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+
+        // Counted against the staker until unstaked, whoever holds the receipt by then
+        self.pool_stats
+            .add_staked(1, self.user_account.amount_staked == 0)?;
+        self.user_account.amount_staked += 1;
+
+        // Fix the receipt supply at one
+        set_authority(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                SetAuthority {
+                    current_authority: self.stake_account.to_account_info(),
+                    account_or_mint: self.receipt_mint.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )?;
+
+        Ok(())
+    }
+}
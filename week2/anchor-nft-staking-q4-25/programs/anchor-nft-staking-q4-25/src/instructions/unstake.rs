@@ -7,7 +7,7 @@ use mpl_core::{
 
 use crate::{
     errors::StakeError,
-    state::{StakeAccount, StakeConfig, UserAccount},
+    state::{PoolStats, StakeAccount, StakeConfig, UserAccount},
 };
 
 #[derive(Accounts)]
@@ -36,7 +36,8 @@ pub struct Unstake<'info> {
         close = user,
         seeds = [b"stake", config.key().as_ref(), asset.key().as_ref()],
         bump = stake_account.bump,
-        constraint = stake_account.owner == user.key() @ StakeError::NotOwner
+        constraint = stake_account.owner == user.key() @ StakeError::NotOwner,
+        constraint = stake_account.receipt_mint.is_none() @ StakeError::ReceiptRequired,
     )]
     pub stake_account: Account<'info, StakeAccount>,
     #[account(
@@ -52,6 +53,13 @@ pub struct Unstake<'info> {
     )]
     pub user_account: Account<'info, UserAccount>,
 
+    #[account(
+        mut,
+        seeds = [b"stats".as_ref(), config.key().as_ref()],
+        bump = pool_stats.bump,
+    )]
+    pub pool_stats: Account<'info, PoolStats>,
+
     /// CHECK: verified by the metaplex core
     #[account(address = CORE_PROGRAM_ID)]
     pub core_program: UncheckedAccount<'info>,
@@ -59,18 +67,17 @@ pub struct Unstake<'info> {
 }
 
 impl<'info> Unstake<'info> {
-    pub fn unstake(&mut self) -> Result<()> {
-        let time_elapsed = (Clock::get()?.unix_timestamp - self.stake_account.staked_at)
-            .checked_div(86400)
-            .unwrap() as u32;
-        require!(
-            time_elapsed >= self.config.freeze_period,
-            StakeError::FreezePeriodNotPassed
-        );
-
-        // Reward Calculation
-        let points_earned = time_elapsed * self.config.points_per_stake as u32;
-        self.user_account.points += points_earned;
+    pub fn unstake(&mut self, early: bool) -> Result<()> {
+        // Reward Calculation, at whatever rates were active while staked
+        let now = Clock::get()?.unix_timestamp;
+        self.config.accrue(now)?;
+        let points_earned = self.stake_account.settle(&self.config, now, early)?;
+        self.user_account.add_points(points_earned)?;
+        self.pool_stats.record_points(
+            self.user.key(),
+            points_earned,
+            self.user_account.lifetime_points,
+        )?;
 
         let signer_seeds: &[&[&[u8]]] = &[&[
             b"stake",
@@ -98,6 +105,8 @@ impl<'info> Unstake<'info> {
             .invoke_signed(signer_seeds)?;
 
         self.user_account.amount_staked -= 1;
+        self.pool_stats
+            .remove_staked(1, self.user_account.amount_staked == 0)?;
 
         Ok(())
     }
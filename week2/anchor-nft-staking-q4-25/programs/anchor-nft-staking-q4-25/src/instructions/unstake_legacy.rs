@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{thaw_delegated_account, MasterEditionAccount, Metadata, ThawDelegatedAccount},
+    token::{revoke, Mint, Revoke, Token, TokenAccount},
+};
+
+use crate::{
+    errors::StakeError,
+    state::{PoolStats, StakeAccount, StakeConfig, UserAccount},
+};
+
+#[derive(Accounts)]
+pub struct UnstakeLegacy<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub nft_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = user,
+    )]
+    pub user_nft_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"metadata", metadata_program.key().as_ref(), nft_mint.key().as_ref(), b"edition"],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub edition: Account<'info, MasterEditionAccount>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"stake", config.key().as_ref(), nft_mint.key().as_ref()],
+        bump = stake_account.bump,
+        constraint = stake_account.owner == user.key() @ StakeError::NotOwner,
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user".as_ref(), user.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stats".as_ref(), config.key().as_ref()],
+        bump = pool_stats.bump,
+    )]
+    pub pool_stats: Account<'info, PoolStats>,
+
+    pub metadata_program: Program<'info, Metadata>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UnstakeLegacy<'info> {
+    pub fn unstake_legacy(&mut self, early: bool) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        self.config.accrue(now)?;
+        let points_earned = self.stake_account.settle(&self.config, now, early)?;
+        self.user_account.add_points(points_earned)?;
+        self.pool_stats.record_points(
+            self.user.key(),
+            points_earned,
+            self.user_account.lifetime_points,
+        )?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"stake",
+            &self.config.key().to_bytes(),
+            &self.nft_mint.key().to_bytes(),
+            &[self.stake_account.bump],
+        ]];
+
+        thaw_delegated_account(CpiContext::new_with_signer(
+            self.metadata_program.to_account_info(),
+            ThawDelegatedAccount {
+                metadata: self.metadata_program.to_account_info(),
+                delegate: self.stake_account.to_account_info(),
+                token_account: self.user_nft_ata.to_account_info(),
+                edition: self.edition.to_account_info(),
+                mint: self.nft_mint.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        revoke(CpiContext::new(
+            self.token_program.to_account_info(),
+            Revoke {
+                source: self.user_nft_ata.to_account_info(),
+                authority: self.user.to_account_info(),
+            },
+        ))?;
+
+        self.user_account.amount_staked -= 1;
+        self.pool_stats
+            .remove_staked(1, self.user_account.amount_staked == 0)?;
+
+        Ok(())
+    }
+}
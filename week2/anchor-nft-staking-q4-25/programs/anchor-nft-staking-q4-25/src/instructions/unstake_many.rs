@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+use mpl_core::{
+    instructions::{RemovePluginV1CpiBuilder, UpdatePluginV1CpiBuilder},
+    types::{FreezeDelegate, Plugin, PluginType},
+    ID as CORE_PROGRAM_ID,
+};
+
+use crate::{
+    errors::StakeError,
+    instructions::batch_size,
+    state::{PoolStats, StakeAccount, StakeConfig, UserAccount},
+};
+
+#[derive(Accounts)]
+pub struct UnstakeMany<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user".as_ref(), user.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stats".as_ref(), config.key().as_ref()],
+        bump = pool_stats.bump,
+    )]
+    pub pool_stats: Account<'info, PoolStats>,
+
+    /// CHECK: verified by the metaplex core
+    #[account(address = CORE_PROGRAM_ID)]
+    pub core_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UnstakeMany<'info> {
+    pub fn unstake_many(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        let count = batch_size(remaining_accounts)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        self.config.accrue(now)?;
+
+        let config_key = self.config.key();
+        let mut points_earned: u32 = 0;
+
+        for accounts in remaining_accounts.chunks(3) {
+            let (asset, collection, stake_account_info) =
+                (&accounts[0], &accounts[1], &accounts[2]);
+
+            require!(
+                asset.owner == &CORE_PROGRAM_ID && !asset.data_is_empty(),
+                StakeError::InvalidAsset
+            );
+            require!(
+                collection.owner == &CORE_PROGRAM_ID && !collection.data_is_empty(),
+                StakeError::InvalidCollection
+            );
+
+            let stake_account = Account::<StakeAccount>::try_from(stake_account_info)?;
+            let stake_key = Pubkey::create_program_address(
+                &[
+                    b"stake",
+                    config_key.as_ref(),
+                    asset.key.as_ref(),
+                    &[stake_account.bump],
+                ],
+                &crate::ID,
+            )
+            .map_err(|_| StakeError::InvalidStakeAccount)?;
+            require_keys_eq!(
+                stake_account.key(),
+                stake_key,
+                StakeError::InvalidStakeAccount
+            );
+            require_keys_eq!(stake_account.owner, self.user.key(), StakeError::NotOwner);
+            require!(
+                stake_account.receipt_mint.is_none(),
+                StakeError::ReceiptRequired
+            );
+
+            points_earned = points_earned
+                .checked_add(stake_account.settle(&self.config, now, false)?)
+                .ok_or(StakeError::Overflow)?;
+
+            let signer_seeds: &[&[&[u8]]] = &[&[
+                b"stake",
+                config_key.as_ref(),
+                asset.key.as_ref(),
+                &[stake_account.bump],
+            ]];
+
+            UpdatePluginV1CpiBuilder::new(&self.core_program.to_account_info())
+                .asset(asset)
+                .collection(Some(collection))
+                .payer(&self.user.to_account_info())
+                .authority(Some(stake_account_info))
+                .system_program(&self.system_program.to_account_info())
+                .plugin(Plugin::FreezeDelegate(FreezeDelegate { frozen: false }))
+                .invoke_signed(signer_seeds)?;
+
+            RemovePluginV1CpiBuilder::new(&self.core_program.to_account_info())
+                .asset(asset)
+                .collection(Some(collection))
+                .payer(&self.user.to_account_info())
+                .authority(None)
+                .system_program(&self.system_program.to_account_info())
+                .plugin_type(PluginType::FreezeDelegate)
+                .invoke_signed(signer_seeds)?;
+
+            stake_account.close(self.user.to_account_info())?;
+        }
+
+        self.user_account.add_points(points_earned)?;
+        self.pool_stats.record_points(
+            self.user.key(),
+            points_earned,
+            self.user_account.lifetime_points,
+        )?;
+
+        self.user_account.amount_staked -= count as u8;
+        self.pool_stats
+            .remove_staked(count as u64, self.user_account.amount_staked == 0)?;
+
+        Ok(())
+    }
+}
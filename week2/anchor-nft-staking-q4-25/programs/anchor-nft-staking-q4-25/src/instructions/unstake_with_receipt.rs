@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{burn, Burn, Mint, Token, TokenAccount};
+use mpl_core::{instructions::TransferV1CpiBuilder, ID as CORE_PROGRAM_ID};
+
+use crate::{
+    errors::StakeError,
+    state::{PoolStats, StakeAccount, StakeConfig, UserAccount},
+};
+
+#[derive(Accounts)]
+pub struct UnstakeWithReceipt<'info> {
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = asset.owner == &CORE_PROGRAM_ID,
+        constraint = !asset.data_is_empty(),
+    )]
+    /// CHECK: asset is verified by the core program
+    pub asset: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = collection.owner == &CORE_PROGRAM_ID,
+        constraint = !collection.data_is_empty(),
+    )]
+    /// CHECK: collection is verified by the core program
+    pub collection: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = staker,
+        seeds = [b"stake", config.key().as_ref(), asset.key().as_ref()],
+        bump = stake_account.bump,
+        constraint = stake_account.receipt_mint == Some(receipt_mint.key()) @ StakeError::NotReceiptHolder,
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    // Paid the stake account's rent, so gets it back
+    #[account(
+        mut,
+        address = stake_account.owner @ StakeError::NotOwner,
+    )]
+    pub staker: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user".as_ref(), stake_account.owner.as_ref()],
+        bump,
+    )]
+    /// CHECK: the staker's user account, which is `user_account` itself when the staker holds the receipt
+    pub staker_account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub receipt_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = receipt_mint,
+        token::authority = holder,
+        constraint = holder_receipt_ata.amount == 1 @ StakeError::NotReceiptHolder,
+    )]
+    pub holder_receipt_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, StakeConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user".as_ref(), holder.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stats".as_ref(), config.key().as_ref()],
+        bump = pool_stats.bump,
+    )]
+    pub pool_stats: Account<'info, PoolStats>,
+
+    /// CHECK: verified by the metaplex core
+    #[account(address = CORE_PROGRAM_ID)]
+    pub core_program: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UnstakeWithReceipt<'info> {
+    // Points and the asset go to whoever holds the receipt, who can then claim them as usual
+    pub fn unstake_with_receipt(&mut self, early: bool) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        self.config.accrue(now)?;
+        let points_earned = self.stake_account.settle(&self.config, now, early)?;
+        self.user_account.add_points(points_earned)?;
+        self.pool_stats.record_points(
+            self.holder.key(),
+            points_earned,
+            self.user_account.lifetime_points,
+        )?;
+
+        burn(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Burn {
+                    mint: self.receipt_mint.to_account_info(),
+                    from: self.holder_receipt_ata.to_account_info(),
+                    authority: self.holder.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"stake",
+            &self.config.key().to_bytes(),
+            &self.asset.key.to_bytes(),
+            &[self.stake_account.bump],
+        ]];
+
+        TransferV1CpiBuilder::new(&self.core_program.to_account_info())
+            .asset(&self.asset.to_account_info())
+            .collection(Some(&self.collection.to_account_info()))
+            .payer(&self.holder.to_account_info())
+            .authority(Some(&self.stake_account.to_account_info()))
+            .new_owner(&self.holder.to_account_info())
+            .system_program(Some(&self.system_program.to_account_info()))
+            .invoke_signed(signer_seeds)?;
+
+        // Loading both as `Account`s would have the last one written back win when they match
+        let amount_staked = if self.staker_account.key() == self.user_account.key() {
+            self.user_account.amount_staked -= 1;
+            self.user_account.amount_staked
+        } else {
+            let info = self.staker_account.to_account_info();
+            let mut staker_account =
+                UserAccount::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            staker_account.amount_staked -= 1;
+            staker_account.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+            staker_account.amount_staked
+        };
+        self.pool_stats.remove_staked(1, amount_staked == 0)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::StakeError,
+    state::{LockTier, StakeConfig, MAX_LOCK_TIERS},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdateConfigArgs {
+    pub points_per_stake: Option<u8>,
+    pub max_stake: Option<u8>,
+    pub freeze_period: Option<u32>,
+    pub early_unstake_penalty_bps: Option<u16>,
+    pub base_reward_per_point: Option<u64>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        has_one = admin @ StakeError::NotAdmin,
+    )]
+    pub config: Account<'info, StakeConfig>,
+}
+
+impl<'info> UpdateConfig<'info> {
+    pub fn update_config(&mut self, args: UpdateConfigArgs) -> Result<()> {
+        if let Some(points_per_stake) = args.points_per_stake {
+            // Settle accrual at the old rate before switching
+            self.config.accrue(Clock::get()?.unix_timestamp)?;
+            self.config.points_per_stake = points_per_stake;
+        }
+        if let Some(max_stake) = args.max_stake {
+            self.config.max_stake = max_stake;
+        }
+        if let Some(freeze_period) = args.freeze_period {
+            self.config.freeze_period = freeze_period;
+        }
+        if let Some(penalty_bps) = args.early_unstake_penalty_bps {
+            require!(penalty_bps <= 10_000, StakeError::InvalidPenalty);
+            self.config.early_unstake_penalty_bps = penalty_bps;
+        }
+        if let Some(base_reward_per_point) = args.base_reward_per_point {
+            self.config.base_reward_per_point = base_reward_per_point;
+        }
+
+        Ok(())
+    }
+
+    // Existing stakes keep the tier terms they were staked with
+    pub fn set_lock_tiers(&mut self, lock_tiers: Vec<LockTier>) -> Result<()> {
+        require!(
+            !lock_tiers.is_empty() && lock_tiers.len() <= MAX_LOCK_TIERS,
+            StakeError::InvalidLockTiers
+        );
+        // Locking up an asset never earns less than leaving it unlocked
+        require!(
+            lock_tiers.iter().all(|tier| tier.multiplier_bps >= 10_000),
+            StakeError::InvalidLockTiers
+        );
+        self.config.lock_tiers = lock_tiers;
+
+        Ok(())
+    }
+
+    pub fn set_paused(&mut self, paused: bool) -> Result<()> {
+        self.config.paused = paused;
+
+        Ok(())
+    }
+}
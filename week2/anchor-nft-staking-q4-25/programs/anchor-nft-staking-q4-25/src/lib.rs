@@ -5,6 +5,7 @@ mod instructions;
 mod state;
 
 use instructions::*;
+use state::LockTier;
 
 declare_id!("GxMCU3iEw93bUGhqCGT9j5GyfVbKfxb533EdEoZrHExf");
 
@@ -17,15 +18,39 @@ pub mod anchor_nft_staking_q4_25 {
         points_per_stake: u8,
         max_stake: u8,
         freeze_period: u32,
+        reward_budget: u64,
+        epoch_duration: i64,
     ) -> Result<()> {
-        ctx.accounts
-            .initialize_config(points_per_stake, max_stake, freeze_period, &ctx.bumps)
+        ctx.accounts.initialize_config(
+            points_per_stake,
+            max_stake,
+            freeze_period,
+            reward_budget,
+            epoch_duration,
+            &ctx.bumps,
+        )
+    }
+
+    pub fn update_config(ctx: Context<UpdateConfig>, args: UpdateConfigArgs) -> Result<()> {
+        ctx.accounts.update_config(args)
+    }
+
+    pub fn set_lock_tiers(ctx: Context<UpdateConfig>, lock_tiers: Vec<LockTier>) -> Result<()> {
+        ctx.accounts.set_lock_tiers(lock_tiers)
+    }
+
+    pub fn set_paused(ctx: Context<UpdateConfig>, paused: bool) -> Result<()> {
+        ctx.accounts.set_paused(paused)
     }
 
     pub fn initialize_user(ctx: Context<Initialize>) -> Result<()> {
         ctx.accounts.initialize_user_account(&ctx.bumps)
     }
 
+    pub fn close_user(ctx: Context<CloseUser>) -> Result<()> {
+        ctx.accounts.close_user()
+    }
+
     pub fn create_collection(
         ctx: Context<CreateCollection>,
         args: CreateCollectionArgs,
@@ -37,15 +62,68 @@ pub mod anchor_nft_staking_q4_25 {
         ctx.accounts.mint_nft()
     }
 
-    pub fn stake(ctx: Context<Stake>) -> Result<()> {
-        ctx.accounts.stake(&ctx.bumps)
+    pub fn stake(ctx: Context<Stake>, tier: u8) -> Result<()> {
+        ctx.accounts.stake(tier, &ctx.bumps)
     }
 
     pub fn unstake(ctx: Context<Unstake>) -> Result<()> {
-        ctx.accounts.unstake()
+        ctx.accounts.unstake(false)
+    }
+
+    pub fn unstake_early(ctx: Context<Unstake>) -> Result<()> {
+        ctx.accounts.unstake(true)
+    }
+
+    pub fn stake_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, StakeMany<'info>>,
+        tier: u8,
+    ) -> Result<()> {
+        ctx.accounts.stake_many(tier, ctx.remaining_accounts)
+    }
+
+    pub fn unstake_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UnstakeMany<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.unstake_many(ctx.remaining_accounts)
+    }
+
+    pub fn stake_with_receipt(ctx: Context<StakeWithReceipt>, tier: u8) -> Result<()> {
+        ctx.accounts.stake_with_receipt(tier, &ctx.bumps)
+    }
+
+    pub fn unstake_with_receipt(ctx: Context<UnstakeWithReceipt>, early: bool) -> Result<()> {
+        ctx.accounts.unstake_with_receipt(early)
+    }
+
+    pub fn allow_legacy_collection(ctx: Context<AllowLegacyCollection>) -> Result<()> {
+        ctx.accounts.allow_legacy_collection(&ctx.bumps)
+    }
+
+    pub fn remove_legacy_collection(ctx: Context<RemoveLegacyCollection>) -> Result<()> {
+        ctx.accounts.remove_legacy_collection()
+    }
+
+    pub fn stake_legacy(ctx: Context<StakeLegacy>, tier: u8) -> Result<()> {
+        ctx.accounts.stake_legacy(tier, &ctx.bumps)
+    }
+
+    pub fn unstake_legacy(ctx: Context<UnstakeLegacy>, early: bool) -> Result<()> {
+        ctx.accounts.unstake_legacy(early)
     }
 
     pub fn claim(ctx: Context<Claim>) -> Result<()> {
         ctx.accounts.claim()
     }
+
+    pub fn list_item(ctx: Context<ListItem>, args: ListItemArgs) -> Result<()> {
+        ctx.accounts.list_item(args, &ctx.bumps)
+    }
+
+    pub fn delist_item(ctx: Context<DelistItem>) -> Result<()> {
+        ctx.accounts.delist_item()
+    }
+
+    pub fn redeem(ctx: Context<Redeem>) -> Result<()> {
+        ctx.accounts.redeem(&ctx.bumps)
+    }
 }
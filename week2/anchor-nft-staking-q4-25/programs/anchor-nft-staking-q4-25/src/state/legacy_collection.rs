@@ -0,0 +1,9 @@
+use anchor_lang::prelude::*;
+
+// Marks a Token Metadata collection whose NFTs may be staked through stake_legacy
+#[account]
+#[derive(InitSpace)]
+pub struct LegacyCollection {
+    pub collection_mint: Pubkey,
+    pub bump: u8,
+}
@@ -7,5 +7,17 @@ pub use stake_config::*;
 pub mod stake_account;
 pub use stake_account::*;
 
+pub mod pool_stats;
+pub use pool_stats::*;
+
 pub mod collection_info;
 pub use collection_info::*;
+
+pub mod legacy_collection;
+pub use legacy_collection::*;
+
+pub mod redeem_item;
+pub use redeem_item::*;
+
+pub mod redemption;
+pub use redemption::*;
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::StakeError;
+
+pub const LEADERBOARD_SIZE: usize = 10;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct LeaderboardEntry {
+    pub user: Pubkey,
+    pub points: u64, // Lifetime points earned
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PoolStats {
+    pub total_staked: u64,
+    pub total_points_issued: u64,
+    pub total_rewards_minted: u64,
+    pub active_stakers: u64, // Users with at least one position staked right now, receipts included
+    #[max_len(LEADERBOARD_SIZE)]
+    pub leaderboard: Vec<LeaderboardEntry>, // Sorted by points, highest first
+    pub bump: u8,
+}
+
+impl PoolStats {
+    // `first` is set when the user had nothing staked before these positions
+    pub fn add_staked(&mut self, count: u64, first: bool) -> Result<()> {
+        if first {
+            self.active_stakers = self
+                .active_stakers
+                .checked_add(1)
+                .ok_or(StakeError::Overflow)?;
+        }
+        self.total_staked = self
+            .total_staked
+            .checked_add(count)
+            .ok_or(StakeError::Overflow)?;
+        Ok(())
+    }
+
+    // `last` is set when the user has nothing left staked after these positions
+    pub fn remove_staked(&mut self, count: u64, last: bool) -> Result<()> {
+        if last {
+            self.active_stakers = self
+                .active_stakers
+                .checked_sub(1)
+                .ok_or(StakeError::Overflow)?;
+        }
+        self.total_staked = self
+            .total_staked
+            .checked_sub(count)
+            .ok_or(StakeError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn record_rewards(&mut self, amount: u64) -> Result<()> {
+        self.total_rewards_minted = self
+            .total_rewards_minted
+            .checked_add(amount)
+            .ok_or(StakeError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn record_points(
+        &mut self,
+        user: Pubkey,
+        points_earned: u32,
+        lifetime_points: u64,
+    ) -> Result<()> {
+        self.total_points_issued = self
+            .total_points_issued
+            .checked_add(points_earned as u64)
+            .ok_or(StakeError::Overflow)?;
+
+        if let Some(entry) = self.leaderboard.iter_mut().find(|entry| entry.user == user) {
+            entry.points = lifetime_points;
+        } else if self.leaderboard.len() < LEADERBOARD_SIZE {
+            self.leaderboard.push(LeaderboardEntry {
+                user,
+                points: lifetime_points,
+            });
+        } else if self.leaderboard[LEADERBOARD_SIZE - 1].points < lifetime_points {
+            self.leaderboard[LEADERBOARD_SIZE - 1] = LeaderboardEntry {
+                user,
+                points: lifetime_points,
+            };
+        } else {
+            return Ok(());
+        }
+
+        self.leaderboard.sort_by(|a, b| b.points.cmp(&a.points));
+        Ok(())
+    }
+
+    // Closed accounts drop off so their spot can go to someone still staking
+    pub fn remove_from_leaderboard(&mut self, user: Pubkey) {
+        self.leaderboard.retain(|entry| entry.user != user);
+    }
+}
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_ITEM_NAME_LEN: usize = 32;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ItemKind {
+    WhitelistSpot,
+    CoreAsset { collection: Pubkey }, // Minted from a collection created by this program
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RedeemItem {
+    pub id: u64,
+    pub kind: ItemKind,
+    #[max_len(MAX_ITEM_NAME_LEN)]
+    pub name: String,
+    pub cost: u32, // Points
+    pub supply: u32,
+    pub redeemed: u32,
+    pub bump: u8,
+}
@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Redemption {
+    pub user: Pubkey,
+    pub item: Pubkey,
+    pub points_spent: u32,
+    pub asset: Option<Pubkey>,
+    pub redeemed_at: i64,
+    pub bump: u8,
+}
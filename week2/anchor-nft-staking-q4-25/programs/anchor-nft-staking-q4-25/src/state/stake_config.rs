@@ -1,11 +1,146 @@
 use anchor_lang::prelude::*;
 
+// Default reward tokens (6 decimals) paid per point during the first emission epoch
+pub const DEFAULT_REWARD_PER_POINT: u64 = 10_000_000;
+
+// Default share of points forfeited when unstaking before the lock ends
+pub const DEFAULT_EARLY_UNSTAKE_PENALTY_BPS: u16 = 2_500;
+
+pub const MAX_LOCK_TIERS: usize = 4;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct LockTier {
+    pub lock_days: u32,
+    pub multiplier_bps: u16, // 10_000 = 1x points, never less
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct StakeConfig {
+    pub admin: Pubkey,
     pub points_per_stake: u8,
     pub max_stake: u8,
     pub freeze_period: u32, // Counted as Days
+    pub paused: bool,
+    pub points_index: u64, // Points accrued per staked asset, in point-seconds
+    pub last_update: i64,
+    pub reward_budget: u64, // Total reward tokens that can ever be minted
+    pub rewards_minted: u64,
+    pub emission_start: i64,
+    pub epoch_duration: i64, // Seconds, the reward per point halves every epoch
+    pub base_reward_per_point: u64, // Points to reward token conversion rate before halving
+    #[max_len(MAX_LOCK_TIERS)]
+    pub lock_tiers: Vec<LockTier>,
+    pub early_unstake_penalty_bps: u16, // Share of points forfeited when unstaking early
     pub rewards_bump: u8,
     pub bump: u8,
 }
+
+impl StakeConfig {
+    // Accrues the current rate up to `now` so a rate change only affects future accrual
+    pub fn accrue(&mut self, now: i64) -> Result<()> {
+        let elapsed = now.saturating_sub(self.last_update).max(0) as u64;
+        self.points_index = elapsed
+            .checked_mul(self.points_per_stake as u64)
+            .and_then(|accrued| self.points_index.checked_add(accrued))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.last_update = now;
+
+        Ok(())
+    }
+
+    // Reward tokens paid per point at `now`, halved once per elapsed epoch
+    pub fn reward_per_point(&self, now: i64) -> u64 {
+        let epoch = (now - self.emission_start).max(0) / self.epoch_duration;
+        self.base_reward_per_point
+            .checked_shr(epoch as u32)
+            .unwrap_or(0)
+    }
+
+    pub fn remaining_budget(&self) -> u64 {
+        self.reward_budget.saturating_sub(self.rewards_minted)
+    }
+
+    // Points the remaining budget can pay for at `now`, and the reward tokens they mint
+    pub fn claimable(&self, points: u32, now: i64) -> (u32, u64) {
+        let reward_per_point = self.reward_per_point(now);
+        let points = (points as u64).min(
+            self.remaining_budget()
+                .checked_div(reward_per_point)
+                .unwrap_or(0),
+        );
+
+        (points as u32, points * reward_per_point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPOCH: i64 = 30 * 86400;
+
+    fn config(reward_budget: u64) -> StakeConfig {
+        StakeConfig {
+            admin: Pubkey::default(),
+            points_per_stake: 10,
+            max_stake: 5,
+            freeze_period: 0,
+            paused: false,
+            points_index: 0,
+            last_update: 0,
+            reward_budget,
+            rewards_minted: 0,
+            emission_start: 0,
+            epoch_duration: EPOCH,
+            base_reward_per_point: DEFAULT_REWARD_PER_POINT,
+            lock_tiers: vec![],
+            early_unstake_penalty_bps: DEFAULT_EARLY_UNSTAKE_PENALTY_BPS,
+            rewards_bump: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn reward_per_point_halves_every_epoch() {
+        let config = config(u64::MAX);
+
+        assert_eq!(config.reward_per_point(EPOCH - 1), DEFAULT_REWARD_PER_POINT);
+        assert_eq!(config.reward_per_point(EPOCH), DEFAULT_REWARD_PER_POINT / 2);
+        assert_eq!(
+            config.reward_per_point(3 * EPOCH),
+            DEFAULT_REWARD_PER_POINT / 8
+        );
+        assert_eq!(config.reward_per_point(64 * EPOCH), 0);
+    }
+
+    #[test]
+    fn claims_stop_at_the_reward_budget() {
+        let mut config = config(10 * DEFAULT_REWARD_PER_POINT);
+        config.rewards_minted = config.reward_budget;
+
+        assert_eq!(config.claimable(100, 0), (0, 0));
+    }
+
+    #[test]
+    fn partial_claim_leaves_the_unpaid_points() {
+        // Budget for 10 points at the first epoch rate, 20 at the halved one
+        let config = config(10 * DEFAULT_REWARD_PER_POINT);
+
+        assert_eq!(config.claimable(25, 0), (10, 10 * DEFAULT_REWARD_PER_POINT));
+        assert_eq!(
+            config.claimable(25, EPOCH),
+            (20, 10 * DEFAULT_REWARD_PER_POINT)
+        );
+        assert_eq!(config.claimable(5, 0), (5, 5 * DEFAULT_REWARD_PER_POINT));
+    }
+
+    #[test]
+    fn accrue_rejects_an_overflowing_index() {
+        let mut config = config(0);
+        config.points_index = u64::MAX - 5;
+
+        assert!(config.accrue(1).is_err());
+        assert!(config.accrue(0).is_ok());
+    }
+}
@@ -1,9 +1,27 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::StakeError;
+
 #[account]
 #[derive(InitSpace)]
 pub struct UserAccount {
     pub points: u32,
+    pub lifetime_points: u64,
     pub amount_staked: u8,
     pub bump: u8,
 }
+
+impl UserAccount {
+    // Credits points from an unstake to both the spendable and lifetime totals
+    pub fn add_points(&mut self, points: u32) -> Result<()> {
+        self.points = self
+            .points
+            .checked_add(points)
+            .ok_or(StakeError::Overflow)?;
+        self.lifetime_points = self
+            .lifetime_points
+            .checked_add(points as u64)
+            .ok_or(StakeError::Overflow)?;
+        Ok(())
+    }
+}
@@ -2,8 +2,28 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { AnchorNftStakingQ425 } from "../target/types/anchor_nft_staking_q4_25";
 import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
-import { getAssociatedTokenAddressSync } from "@solana/spl-token";
+import {
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  transfer,
+} from "@solana/spl-token";
 import { MPL_CORE_PROGRAM_ID } from "@metaplex-foundation/mpl-core";
+import {
+  createNft,
+  findMetadataPda,
+  mplTokenMetadata,
+  MPL_TOKEN_METADATA_PROGRAM_ID,
+  verifyCollectionV1,
+} from "@metaplex-foundation/mpl-token-metadata";
+import {
+  generateSigner,
+  keypairIdentity,
+  percentAmount,
+  publicKey as umiPublicKey,
+  some,
+} from "@metaplex-foundation/umi";
+import { createUmi } from "@metaplex-foundation/umi-bundle-defaults";
 import { assert } from "chai";
 
 describe("anchor-nft-staking-q4-25", () => {
@@ -24,6 +44,8 @@ describe("anchor-nft-staking-q4-25", () => {
   const pointsPerStake = 10;
   const maxStake = 5;
   const freezePeriod = 0; // 0 days for testing
+  const rewardBudget = new anchor.BN(1_000_000_000_000); // 1M reward tokens
+  const epochDuration = new anchor.BN(30 * 86400); // Halve every 30 days
 
   // PDAs
   let configPda: PublicKey;
@@ -32,6 +54,7 @@ describe("anchor-nft-staking-q4-25", () => {
   let collectionInfoPda: PublicKey;
   let stakeAccountPda: PublicKey;
   let rewardsAtaPda: PublicKey;
+  let poolStatsPda: PublicKey;
 
   console.log(`Admin: ${admin.publicKey.toString()}`);
   console.log(`User: ${user.publicKey.toString()}`);
@@ -56,6 +79,12 @@ describe("anchor-nft-staking-q4-25", () => {
     )[0];
     console.log(`Reward Mint PDA: ${rewardMintPda.toString()}`);
 
+    poolStatsPda = PublicKey.findProgramAddressSync(
+      [Buffer.from("stats"), configPda.toBuffer()],
+      program.programId
+    )[0];
+    console.log(`Pool Stats PDA: ${poolStatsPda.toString()}`);
+
     userAccountPda = PublicKey.findProgramAddressSync(
       [Buffer.from("user"), user.publicKey.toBuffer()],
       program.programId
@@ -84,10 +113,17 @@ describe("anchor-nft-staking-q4-25", () => {
   describe("Initialize Config", () => {
     it("Initialize the staking config", async () => {
       const tx = await program.methods
-        .initializeConfig(pointsPerStake, maxStake, freezePeriod)
+        .initializeConfig(
+          pointsPerStake,
+          maxStake,
+          freezePeriod,
+          rewardBudget,
+          epochDuration
+        )
         .accountsStrict({
           admin: admin.publicKey,
           config: configPda,
+          poolStats: poolStatsPda,
           rewardMint: rewardMintPda,
           tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
@@ -100,10 +136,109 @@ describe("anchor-nft-staking-q4-25", () => {
       assert.equal(config.pointsPerStake, pointsPerStake);
       assert.equal(config.maxStake, maxStake);
       assert.equal(config.freezePeriod, freezePeriod);
+      assert.equal(config.admin.toString(), admin.publicKey.toString());
+      assert.equal(config.paused, false);
+      assert.ok(config.rewardBudget.eq(rewardBudget));
+      assert.ok(config.rewardsMinted.eqn(0));
       console.log("Config initialized successfully");
     });
   });
 
+  describe("Update Config", () => {
+    it("Update the staking config as admin", async () => {
+      const tx = await program.methods
+        .updateConfig({
+          pointsPerStake: pointsPerStake,
+          maxStake: maxStake,
+          freezePeriod: null,
+          earlyUnstakePenaltyBps: 5_000,
+          baseRewardPerPoint: new anchor.BN(10_000_000),
+        })
+        .accountsStrict({
+          admin: admin.publicKey,
+          config: configPda,
+        })
+        .rpc();
+
+      console.log(`Update Config tx: ${tx}`);
+
+      const config = await program.account.stakeConfig.fetch(configPda);
+      assert.equal(config.pointsPerStake, pointsPerStake);
+      assert.equal(config.maxStake, maxStake);
+      assert.equal(config.freezePeriod, freezePeriod);
+      assert.equal(config.earlyUnstakePenaltyBps, 5_000);
+    });
+
+    it("Set 7, 30 and 90 day lock tiers", async () => {
+      const lockTiers = [
+        { lockDays: 0, multiplierBps: 10_000 },
+        { lockDays: 7, multiplierBps: 12_500 },
+        { lockDays: 30, multiplierBps: 15_000 },
+        { lockDays: 90, multiplierBps: 20_000 },
+      ];
+
+      await program.methods
+        .setLockTiers(lockTiers)
+        .accountsStrict({ admin: admin.publicKey, config: configPda })
+        .rpc();
+
+      const config = await program.account.stakeConfig.fetch(configPda);
+      assert.equal(config.lockTiers.length, lockTiers.length);
+      assert.equal(config.lockTiers[3].lockDays, 90);
+      assert.equal(config.lockTiers[3].multiplierBps, 20_000);
+    });
+
+    it("Reject lock tiers that earn less than 1x", async () => {
+      try {
+        await program.methods
+          .setLockTiers([{ lockDays: 7, multiplierBps: 0 }])
+          .accountsStrict({ admin: admin.publicKey, config: configPda })
+          .rpc();
+        assert.fail("A 0x tier should be rejected");
+      } catch (err) {
+        assert.include(err.toString(), "InvalidLockTiers");
+      }
+    });
+
+    it("Reject config updates from a non-admin", async () => {
+      try {
+        await program.methods
+          .updateConfig({
+            pointsPerStake: 255,
+            maxStake: null,
+            freezePeriod: null,
+            earlyUnstakePenaltyBps: null,
+            baseRewardPerPoint: null,
+          })
+          .accountsStrict({
+            admin: user.publicKey,
+            config: configPda,
+          })
+          .signers([user])
+          .rpc();
+        assert.fail("Non-admin should not update the config");
+      } catch (err) {
+        assert.include(err.toString(), "NotAdmin");
+      }
+    });
+
+    it("Pause and unpause staking", async () => {
+      await program.methods
+        .setPaused(true)
+        .accountsStrict({ admin: admin.publicKey, config: configPda })
+        .rpc();
+      let config = await program.account.stakeConfig.fetch(configPda);
+      assert.equal(config.paused, true);
+
+      await program.methods
+        .setPaused(false)
+        .accountsStrict({ admin: admin.publicKey, config: configPda })
+        .rpc();
+      config = await program.account.stakeConfig.fetch(configPda);
+      assert.equal(config.paused, false);
+    });
+  });
+
   describe("Initialize User", () => {
     it("Initialize a user account", async () => {
       const tx = await program.methods
@@ -199,13 +334,14 @@ describe("anchor-nft-staking-q4-25", () => {
       assert.equal(userAccountBefore.amountStaked, 0);
 
       const tx = await program.methods
-        .stake()
+        .stake(0)
         .accountsStrict({
           user: user.publicKey,
           asset: asset.publicKey,
           collection: collection.publicKey,
           stakeAccount: stakeAccountPda,
           config: configPda,
+          poolStats: poolStatsPda,
           userAccount: userAccountPda,
           coreProgram: MPL_CORE_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
@@ -221,11 +357,17 @@ describe("anchor-nft-staking-q4-25", () => {
       assert.equal(stakeAccount.owner.toString(), user.publicKey.toString());
       assert.equal(stakeAccount.mint.toString(), asset.publicKey.toString());
       assert.ok(stakeAccount.stakedAt > new anchor.BN(0));
+      assert.equal(stakeAccount.lockDays, 0);
+      assert.equal(stakeAccount.multiplierBps, 10_000);
 
       const userAccountAfter = await program.account.userAccount.fetch(
         userAccountPda
       );
       assert.equal(userAccountAfter.amountStaked, 1);
+
+      const poolStats = await program.account.poolStats.fetch(poolStatsPda);
+      assert.ok(poolStats.totalStaked.eqn(1));
+      assert.ok(poolStats.activeStakers.eqn(1));
       console.log("NFT staked successfully");
     });
   });
@@ -251,6 +393,7 @@ describe("anchor-nft-staking-q4-25", () => {
           collection: collection.publicKey,
           stakeAccount: stakeAccountPda,
           config: configPda,
+          poolStats: poolStatsPda,
           userAccount: userAccountPda,
           coreProgram: MPL_CORE_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
@@ -275,6 +418,11 @@ describe("anchor-nft-staking-q4-25", () => {
       assert.equal(userAccountAfter.amountStaked, 0);
       // Points should be awarded (time_elapsed * points_per_stake)
       assert.ok(userAccountAfter.points >= pointsBefore);
+
+      const poolStats = await program.account.poolStats.fetch(poolStatsPda);
+      assert.ok(poolStats.totalStaked.eqn(0));
+      assert.ok(poolStats.activeStakers.eqn(0));
+      assert.ok(poolStats.leaderboard.length <= 10);
       console.log(
         `NFT unstaked successfully, points earned: ${
           userAccountAfter.points - pointsBefore
@@ -283,6 +431,744 @@ describe("anchor-nft-staking-q4-25", () => {
     });
   });
 
+  describe("Paused Staking", () => {
+    const pausedAsset = Keypair.generate();
+    const newAsset = Keypair.generate();
+    const stakePda = (asset: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), configPda.toBuffer(), asset.toBuffer()],
+        program.programId
+      )[0];
+
+    before(async () => {
+      for (const nft of [pausedAsset, newAsset]) {
+        await program.methods
+          .mintNft()
+          .accountsStrict({
+            minter: user.publicKey,
+            asset: nft.publicKey,
+            collection: collection.publicKey,
+            collectionInfo: collectionInfoPda,
+            coreProgram: MPL_CORE_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user, nft])
+          .rpc();
+      }
+
+      // Staked before the pause, so there is something to unstake during it
+      await program.methods
+        .stake(0)
+        .accountsStrict({
+          user: user.publicKey,
+          asset: pausedAsset.publicKey,
+          collection: collection.publicKey,
+          stakeAccount: stakePda(pausedAsset.publicKey),
+          config: configPda,
+          poolStats: poolStatsPda,
+          userAccount: userAccountPda,
+          coreProgram: MPL_CORE_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+    });
+
+    after(async () => {
+      await program.methods
+        .setPaused(false)
+        .accountsStrict({ admin: admin.publicKey, config: configPda })
+        .rpc();
+    });
+
+    it("Reject pausing as a non-admin", async () => {
+      try {
+        await program.methods
+          .setPaused(true)
+          .accountsStrict({ admin: user.publicKey, config: configPda })
+          .signers([user])
+          .rpc();
+        assert.fail("Non-admin should not pause staking");
+      } catch (err) {
+        assert.include(err.toString(), "NotAdmin");
+      }
+    });
+
+    it("Reject new stakes while paused", async () => {
+      await program.methods
+        .setPaused(true)
+        .accountsStrict({ admin: admin.publicKey, config: configPda })
+        .rpc();
+
+      try {
+        await program.methods
+          .stake(0)
+          .accountsStrict({
+            user: user.publicKey,
+            asset: newAsset.publicKey,
+            collection: collection.publicKey,
+            stakeAccount: stakePda(newAsset.publicKey),
+            config: configPda,
+            poolStats: poolStatsPda,
+            userAccount: userAccountPda,
+            coreProgram: MPL_CORE_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user])
+          .rpc();
+        assert.fail("Stake should be rejected while paused");
+      } catch (err) {
+        assert.include(err.toString(), "StakingPaused");
+      }
+
+      try {
+        await program.methods
+          .stakeMany(0)
+          .accountsStrict({
+            user: user.publicKey,
+            config: configPda,
+            poolStats: poolStatsPda,
+            userAccount: userAccountPda,
+            coreProgram: MPL_CORE_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .remainingAccounts([
+            { pubkey: newAsset.publicKey, isSigner: false, isWritable: true },
+            { pubkey: collection.publicKey, isSigner: false, isWritable: true },
+            {
+              pubkey: stakePda(newAsset.publicKey),
+              isSigner: false,
+              isWritable: true,
+            },
+          ])
+          .signers([user])
+          .rpc();
+        assert.fail("Stake many should be rejected while paused");
+      } catch (err) {
+        assert.include(err.toString(), "StakingPaused");
+      }
+
+      const receiptMint = PublicKey.findProgramAddressSync(
+        [Buffer.from("receipt"), stakePda(newAsset.publicKey).toBuffer()],
+        program.programId
+      )[0];
+      try {
+        await program.methods
+          .stakeWithReceipt(0)
+          .accountsStrict({
+            user: user.publicKey,
+            asset: newAsset.publicKey,
+            collection: collection.publicKey,
+            stakeAccount: stakePda(newAsset.publicKey),
+            receiptMint,
+            userReceiptAta: getAssociatedTokenAddressSync(
+              receiptMint,
+              user.publicKey
+            ),
+            config: configPda,
+            userAccount: userAccountPda,
+            poolStats: poolStatsPda,
+            coreProgram: MPL_CORE_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+          })
+          .signers([user])
+          .rpc();
+        assert.fail("Stake with receipt should be rejected while paused");
+      } catch (err) {
+        assert.include(err.toString(), "StakingPaused");
+      }
+    });
+
+    it("Still allow unstake and claim while paused", async () => {
+      await program.methods
+        .unstake()
+        .accountsStrict({
+          user: user.publicKey,
+          asset: pausedAsset.publicKey,
+          collection: collection.publicKey,
+          stakeAccount: stakePda(pausedAsset.publicKey),
+          config: configPda,
+          poolStats: poolStatsPda,
+          userAccount: userAccountPda,
+          coreProgram: MPL_CORE_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+      assert.isNull(
+        await connection.getAccountInfo(stakePda(pausedAsset.publicKey))
+      );
+
+      // The pause never gates claims, without points yet it stops at NoPoints instead
+      const { points } = await program.account.userAccount.fetch(
+        userAccountPda
+      );
+      const claim = program.methods
+        .claim()
+        .accountsStrict({
+          user: user.publicKey,
+          userRewardAta: rewardsAtaPda,
+          config: configPda,
+          poolStats: poolStatsPda,
+          userAccount: userAccountPda,
+          rewardMint: rewardMintPda,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user]);
+      if (points > 0) {
+        await claim.rpc();
+      } else {
+        try {
+          await claim.rpc();
+          assert.fail("Claim should need points");
+        } catch (err) {
+          assert.include(err.toString(), "NoPoints");
+        }
+      }
+    });
+  });
+
+  describe("Lock Tiers", () => {
+    const lockedAsset = Keypair.generate();
+    let lockedStakePda: PublicKey;
+    const unstakeAccounts = () => ({
+      user: user.publicKey,
+      asset: lockedAsset.publicKey,
+      collection: collection.publicKey,
+      stakeAccount: lockedStakePda,
+      config: configPda,
+      poolStats: poolStatsPda,
+      userAccount: userAccountPda,
+      coreProgram: MPL_CORE_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    });
+
+    before(async () => {
+      lockedStakePda = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("stake"),
+          configPda.toBuffer(),
+          lockedAsset.publicKey.toBuffer(),
+        ],
+        program.programId
+      )[0];
+
+      await program.methods
+        .mintNft()
+        .accountsStrict({
+          minter: user.publicKey,
+          asset: lockedAsset.publicKey,
+          collection: collection.publicKey,
+          collectionInfo: collectionInfoPda,
+          coreProgram: MPL_CORE_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user, lockedAsset])
+        .rpc();
+
+      // Tier 1 locks the asset for 7 days
+      await program.methods
+        .stake(1)
+        .accountsStrict(unstakeAccounts())
+        .signers([user])
+        .rpc();
+    });
+
+    it("Refuse a normal unstake inside the lock", async () => {
+      try {
+        await program.methods
+          .unstake()
+          .accountsStrict(unstakeAccounts())
+          .signers([user])
+          .rpc();
+        assert.fail("Unstake should wait for the lock to end");
+      } catch (err) {
+        assert.include(err.toString(), "FreezePeriodNotPassed");
+      }
+    });
+
+    it("Unstake early inside the lock", async () => {
+      const before = await program.account.userAccount.fetch(userAccountPda);
+
+      const tx = await program.methods
+        .unstakeEarly()
+        .accountsStrict(unstakeAccounts())
+        .signers([user])
+        .rpc();
+
+      console.log(`Unstake Early tx: ${tx}`);
+
+      assert.isNull(await connection.getAccountInfo(lockedStakePda));
+      const after = await program.account.userAccount.fetch(userAccountPda);
+      assert.equal(after.amountStaked, before.amountStaked - 1);
+    });
+  });
+
+  describe("Batch Stake and Unstake", () => {
+    const batchAssets = [Keypair.generate(), Keypair.generate()];
+    const batchAccounts = () =>
+      batchAssets.flatMap((batchAsset) => [
+        { pubkey: batchAsset.publicKey, isSigner: false, isWritable: true },
+        { pubkey: collection.publicKey, isSigner: false, isWritable: true },
+        {
+          pubkey: PublicKey.findProgramAddressSync(
+            [
+              Buffer.from("stake"),
+              configPda.toBuffer(),
+              batchAsset.publicKey.toBuffer(),
+            ],
+            program.programId
+          )[0],
+          isSigner: false,
+          isWritable: true,
+        },
+      ]);
+
+    before(async () => {
+      for (const batchAsset of batchAssets) {
+        await program.methods
+          .mintNft()
+          .accountsStrict({
+            minter: user.publicKey,
+            asset: batchAsset.publicKey,
+            collection: collection.publicKey,
+            collectionInfo: collectionInfoPda,
+            coreProgram: MPL_CORE_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user, batchAsset])
+          .rpc();
+      }
+    });
+
+    it("Stake several NFTs in one instruction", async () => {
+      const tx = await program.methods
+        .stakeMany(0)
+        .accountsStrict({
+          user: user.publicKey,
+          config: configPda,
+          poolStats: poolStatsPda,
+          userAccount: userAccountPda,
+          coreProgram: MPL_CORE_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(batchAccounts())
+        .signers([user])
+        .rpc();
+
+      console.log(`Stake Many tx: ${tx}`);
+
+      const userAccount = await program.account.userAccount.fetch(
+        userAccountPda
+      );
+      assert.equal(userAccount.amountStaked, batchAssets.length);
+    });
+
+    it("Unstake several NFTs in one instruction", async () => {
+      const tx = await program.methods
+        .unstakeMany()
+        .accountsStrict({
+          user: user.publicKey,
+          config: configPda,
+          poolStats: poolStatsPda,
+          userAccount: userAccountPda,
+          coreProgram: MPL_CORE_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(batchAccounts())
+        .signers([user])
+        .rpc();
+
+      console.log(`Unstake Many tx: ${tx}`);
+
+      const userAccount = await program.account.userAccount.fetch(
+        userAccountPda
+      );
+      assert.equal(userAccount.amountStaked, 0);
+    });
+  });
+
+  describe("Receipt Positions", () => {
+    const receiptAsset = Keypair.generate();
+    const holder = Keypair.generate();
+    let holderAccountPda: PublicKey;
+    let receiptStakePda: PublicKey;
+    let receiptMintPda: PublicKey;
+    let receiptAta: PublicKey;
+    let holderReceiptAta: PublicKey;
+
+    // A Core asset account stores its owner right after the key byte
+    const assetOwner = async (asset: PublicKey) => {
+      const info = await connection.getAccountInfo(asset);
+      return new PublicKey(info.data.subarray(1, 33));
+    };
+
+    before(async () => {
+      await connection.requestAirdrop(holder.publicKey, 1_000_000_000);
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+
+      await program.methods
+        .mintNft()
+        .accountsStrict({
+          minter: user.publicKey,
+          asset: receiptAsset.publicKey,
+          collection: collection.publicKey,
+          collectionInfo: collectionInfoPda,
+          coreProgram: MPL_CORE_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user, receiptAsset])
+        .rpc();
+
+      holderAccountPda = PublicKey.findProgramAddressSync(
+        [Buffer.from("user"), holder.publicKey.toBuffer()],
+        program.programId
+      )[0];
+      await program.methods
+        .initializeUser()
+        .accountsStrict({
+          user: holder.publicKey,
+          userAccount: holderAccountPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([holder])
+        .rpc();
+
+      receiptStakePda = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("stake"),
+          configPda.toBuffer(),
+          receiptAsset.publicKey.toBuffer(),
+        ],
+        program.programId
+      )[0];
+      receiptMintPda = PublicKey.findProgramAddressSync(
+        [Buffer.from("receipt"), receiptStakePda.toBuffer()],
+        program.programId
+      )[0];
+      receiptAta = getAssociatedTokenAddressSync(receiptMintPda, user.publicKey);
+    });
+
+    it("Stake an NFT and receive a receipt token", async () => {
+      const before = await program.account.userAccount.fetch(userAccountPda);
+
+      const tx = await program.methods
+        .stakeWithReceipt(0)
+        .accountsStrict({
+          user: user.publicKey,
+          asset: receiptAsset.publicKey,
+          collection: collection.publicKey,
+          stakeAccount: receiptStakePda,
+          receiptMint: receiptMintPda,
+          userReceiptAta: receiptAta,
+          config: configPda,
+          userAccount: userAccountPda,
+          poolStats: poolStatsPda,
+          coreProgram: MPL_CORE_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc();
+
+      console.log(`Stake With Receipt tx: ${tx}`);
+
+      const stakeAccount = await program.account.stakeAccount.fetch(
+        receiptStakePda
+      );
+      assert.equal(
+        stakeAccount.receiptMint.toString(),
+        receiptMintPda.toString()
+      );
+      const balance = await connection.getTokenAccountBalance(receiptAta);
+      assert.equal(balance.value.amount, "1");
+
+      // Escrowed with the stake account and counted against the staker
+      assert.equal(
+        (await assetOwner(receiptAsset.publicKey)).toString(),
+        receiptStakePda.toString()
+      );
+      const after = await program.account.userAccount.fetch(userAccountPda);
+      assert.equal(after.amountStaked, before.amountStaked + 1);
+    });
+
+    it("Unstake the position as a later receipt holder", async () => {
+      holderReceiptAta = (
+        await getOrCreateAssociatedTokenAccount(
+          connection,
+          holder,
+          receiptMintPda,
+          holder.publicKey
+        )
+      ).address;
+      await transfer(connection, user, receiptAta, holderReceiptAta, user, 1);
+
+      const stakerBefore = await program.account.userAccount.fetch(
+        userAccountPda
+      );
+      const stakerLamports = await connection.getBalance(user.publicKey);
+      const rent = await connection.getBalance(receiptStakePda);
+
+      const tx = await program.methods
+        .unstakeWithReceipt(false)
+        .accountsStrict({
+          holder: holder.publicKey,
+          asset: receiptAsset.publicKey,
+          collection: collection.publicKey,
+          stakeAccount: receiptStakePda,
+          staker: user.publicKey,
+          stakerAccount: userAccountPda,
+          receiptMint: receiptMintPda,
+          holderReceiptAta,
+          config: configPda,
+          userAccount: holderAccountPda,
+          poolStats: poolStatsPda,
+          coreProgram: MPL_CORE_PROGRAM_ID,
+          tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([holder])
+        .rpc();
+
+      console.log(`Unstake With Receipt tx: ${tx}`);
+
+      const balance = await connection.getTokenAccountBalance(holderReceiptAta);
+      assert.equal(balance.value.amount, "0");
+
+      // The asset follows the receipt, the rent goes back to the staker
+      assert.equal(
+        (await assetOwner(receiptAsset.publicKey)).toString(),
+        holder.publicKey.toString()
+      );
+      assert.equal(
+        await connection.getBalance(user.publicKey),
+        stakerLamports + rent
+      );
+      const stakerAfter = await program.account.userAccount.fetch(
+        userAccountPda
+      );
+      assert.equal(stakerAfter.amountStaked, stakerBefore.amountStaked - 1);
+    });
+  });
+
+  describe("Legacy Collections", () => {
+    const metadataProgram = new PublicKey(MPL_TOKEN_METADATA_PROGRAM_ID);
+    let collectionMint: PublicKey;
+    let legacyCollectionPda: PublicKey;
+    let nftMint: PublicKey;
+    let userNftAta: PublicKey;
+    let legacyStakePda: PublicKey;
+
+    const metadataAddress = (mint: PublicKey, ...suffix: Buffer[]) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("metadata"),
+          metadataProgram.toBuffer(),
+          mint.toBuffer(),
+          ...suffix,
+        ],
+        metadataProgram
+      )[0];
+    const stakeLegacyAccounts = () => ({
+      user: user.publicKey,
+      nftMint,
+      collectionMint,
+      legacyCollection: legacyCollectionPda,
+      userNftAta,
+      metadata: metadataAddress(nftMint),
+      edition: metadataAddress(nftMint, Buffer.from("edition")),
+      stakeAccount: legacyStakePda,
+      config: configPda,
+      userAccount: userAccountPda,
+      poolStats: poolStatsPda,
+      metadataProgram,
+      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    });
+
+    before(async () => {
+      // A verified Token Metadata collection, with one NFT in it owned by the user
+      const umi = createUmi(connection.rpcEndpoint).use(mplTokenMetadata());
+      umi.use(
+        keypairIdentity(
+          umi.eddsa.createKeypairFromSecretKey(admin.payer.secretKey)
+        )
+      );
+
+      const collectionNft = generateSigner(umi);
+      await createNft(umi, {
+        mint: collectionNft,
+        name: "Legacy Collection",
+        uri: "https://example.com/legacy-collection.json",
+        sellerFeeBasisPoints: percentAmount(0),
+        isCollection: true,
+      }).sendAndConfirm(umi);
+
+      const legacyNft = generateSigner(umi);
+      await createNft(umi, {
+        mint: legacyNft,
+        name: "Legacy NFT",
+        uri: "https://example.com/legacy-nft.json",
+        sellerFeeBasisPoints: percentAmount(0),
+        tokenOwner: umiPublicKey(user.publicKey.toBase58()),
+        collection: some({ key: collectionNft.publicKey, verified: false }),
+      }).sendAndConfirm(umi);
+
+      await verifyCollectionV1(umi, {
+        metadata: findMetadataPda(umi, { mint: legacyNft.publicKey }),
+        collectionMint: collectionNft.publicKey,
+        authority: umi.identity,
+      }).sendAndConfirm(umi);
+
+      collectionMint = new PublicKey(collectionNft.publicKey);
+      nftMint = new PublicKey(legacyNft.publicKey);
+      userNftAta = getAssociatedTokenAddressSync(nftMint, user.publicKey);
+      legacyCollectionPda = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("legacy_collection"),
+          configPda.toBuffer(),
+          collectionMint.toBuffer(),
+        ],
+        program.programId
+      )[0];
+      legacyStakePda = PublicKey.findProgramAddressSync(
+        [Buffer.from("stake"), configPda.toBuffer(), nftMint.toBuffer()],
+        program.programId
+      )[0];
+    });
+
+    it("Reject allowing a collection as a non-admin", async () => {
+      try {
+        await program.methods
+          .allowLegacyCollection()
+          .accountsStrict({
+            admin: user.publicKey,
+            config: configPda,
+            collectionMint,
+            legacyCollection: legacyCollectionPda,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user])
+          .rpc();
+        assert.fail("Only the admin should be able to allow a collection");
+      } catch (err) {
+        assert.include(err.toString(), "NotAdmin");
+      }
+    });
+
+    it("Reject staking from a collection that isn't allowed", async () => {
+      try {
+        await program.methods
+          .stakeLegacy(0)
+          .accountsStrict(stakeLegacyAccounts())
+          .signers([user])
+          .rpc();
+        assert.fail("Staking should need the collection to be allowed");
+      } catch (err) {
+        assert.include(err.toString(), "AccountNotInitialized");
+      }
+    });
+
+    it("Allow a legacy collection", async () => {
+      const tx = await program.methods
+        .allowLegacyCollection()
+        .accountsStrict({
+          admin: admin.publicKey,
+          config: configPda,
+          collectionMint,
+          legacyCollection: legacyCollectionPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      console.log(`Allow Legacy Collection tx: ${tx}`);
+
+      const legacyCollection = await program.account.legacyCollection.fetch(
+        legacyCollectionPda
+      );
+      assert.equal(
+        legacyCollection.collectionMint.toString(),
+        collectionMint.toString()
+      );
+    });
+
+    it("Stake a Token Metadata NFT by freezing it in place", async () => {
+      const before = await program.account.userAccount.fetch(userAccountPda);
+
+      const tx = await program.methods
+        .stakeLegacy(0)
+        .accountsStrict(stakeLegacyAccounts())
+        .signers([user])
+        .rpc();
+
+      console.log(`Stake Legacy tx: ${tx}`);
+
+      const nftAccount = await getAccount(connection, userNftAta);
+      assert.equal(Number(nftAccount.amount), 1);
+      assert.isTrue(nftAccount.isFrozen);
+      assert.equal(nftAccount.delegate.toString(), legacyStakePda.toString());
+
+      const stakeAccount = await program.account.stakeAccount.fetch(
+        legacyStakePda
+      );
+      assert.equal(stakeAccount.owner.toString(), user.publicKey.toString());
+      assert.equal(stakeAccount.mint.toString(), nftMint.toString());
+      const after = await program.account.userAccount.fetch(userAccountPda);
+      assert.equal(after.amountStaked, before.amountStaked + 1);
+    });
+
+    it("Thaw the NFT and revoke the delegate on unstake", async () => {
+      const before = await program.account.userAccount.fetch(userAccountPda);
+
+      const tx = await program.methods
+        .unstakeLegacy(false)
+        .accountsStrict({
+          user: user.publicKey,
+          nftMint,
+          userNftAta,
+          edition: metadataAddress(nftMint, Buffer.from("edition")),
+          stakeAccount: legacyStakePda,
+          config: configPda,
+          userAccount: userAccountPda,
+          poolStats: poolStatsPda,
+          metadataProgram,
+          tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+
+      console.log(`Unstake Legacy tx: ${tx}`);
+
+      const nftAccount = await getAccount(connection, userNftAta);
+      assert.equal(Number(nftAccount.amount), 1);
+      assert.isFalse(nftAccount.isFrozen);
+      assert.isNull(nftAccount.delegate);
+
+      assert.isNull(await connection.getAccountInfo(legacyStakePda));
+      const after = await program.account.userAccount.fetch(userAccountPda);
+      assert.equal(after.amountStaked, before.amountStaked - 1);
+    });
+
+    it("Remove a legacy collection", async () => {
+      await program.methods
+        .removeLegacyCollection()
+        .accountsStrict({
+          admin: admin.publicKey,
+          config: configPda,
+          legacyCollection: legacyCollectionPda,
+        })
+        .rpc();
+
+      assert.isNull(await connection.getAccountInfo(legacyCollectionPda));
+    });
+  });
+
   describe("Claim Rewards", () => {
     it("Claim reward tokens based on points", async () => {
       const userAccountBefore = await program.account.userAccount.fetch(
@@ -301,6 +1187,7 @@ describe("anchor-nft-staking-q4-25", () => {
           user: user.publicKey,
           userRewardAta: rewardsAtaPda,
           config: configPda,
+          poolStats: poolStatsPda,
           userAccount: userAccountPda,
           rewardMint: rewardMintPda,
           associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
@@ -317,10 +1204,193 @@ describe("anchor-nft-staking-q4-25", () => {
       );
       assert.equal(userAccountAfter.points, 0, "Points should be reset to 0");
 
+      const config = await program.account.stakeConfig.fetch(configPda);
+      assert.ok(config.rewardsMinted.lte(config.rewardBudget));
+
+      const poolStats = await program.account.poolStats.fetch(poolStatsPda);
+      assert.ok(poolStats.totalRewardsMinted.eq(config.rewardsMinted));
+
       // Check token balance
       const rewardsAta = await connection.getAccountInfo(rewardsAtaPda);
       assert.ok(rewardsAta, "Rewards ATA should exist");
       console.log("Rewards claimed successfully");
     });
   });
+
+  describe("Points Marketplace", () => {
+    const itemId = new anchor.BN(1);
+    let itemPda: PublicKey;
+
+    const itemAddress = (id: anchor.BN) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("item"),
+          configPda.toBuffer(),
+          id.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+    // Redemptions are numbered per item, in the order they happen
+    const redemptionAddress = (item: PublicKey, index: number) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("redemption"),
+          item.toBuffer(),
+          new anchor.BN(index).toArrayLike(Buffer, "le", 4),
+        ],
+        program.programId
+      )[0];
+    const redeem = (item: PublicKey, index: number) =>
+      program.methods
+        .redeem()
+        .accountsStrict({
+          user: user.publicKey,
+          config: configPda,
+          userAccount: userAccountPda,
+          item,
+          redemption: redemptionAddress(item, index),
+          asset: null,
+          collection: null,
+          collectionInfo: null,
+          coreProgram: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+
+    before(() => {
+      itemPda = itemAddress(itemId);
+    });
+
+    it("List a whitelist spot", async () => {
+      const tx = await program.methods
+        .listItem({
+          id: itemId,
+          kind: { whitelistSpot: {} },
+          name: "Whitelist Spot",
+          cost: 1_000_000,
+          supply: 10,
+        })
+        .accountsStrict({
+          admin: admin.publicKey,
+          config: configPda,
+          item: itemPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      console.log(`List Item tx: ${tx}`);
+
+      const item = await program.account.redeemItem.fetch(itemPda);
+      assert.equal(item.cost, 1_000_000);
+      assert.equal(item.supply, 10);
+      assert.equal(item.redeemed, 0);
+    });
+
+    it("Reject an item name longer than 32 bytes", async () => {
+      const longId = new anchor.BN(3);
+      try {
+        await program.methods
+          .listItem({
+            id: longId,
+            kind: { whitelistSpot: {} },
+            name: "x".repeat(33),
+            cost: 1,
+            supply: 1,
+          })
+          .accountsStrict({
+            admin: admin.publicKey,
+            config: configPda,
+            item: itemAddress(longId),
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+        assert.fail("An overlong name should be rejected");
+      } catch (err) {
+        assert.include(err.toString(), "ItemNameTooLong");
+      }
+    });
+
+    it("Reject a redemption the user cannot afford", async () => {
+      try {
+        await redeem(itemPda, 0);
+        assert.fail("Redeem should fail without enough points");
+      } catch (err) {
+        assert.include(err.toString(), "InsufficientPoints");
+      }
+    });
+
+    it("Redeem the same item more than once", async () => {
+      const freeId = new anchor.BN(2);
+      const freeItem = itemAddress(freeId);
+      await program.methods
+        .listItem({
+          id: freeId,
+          kind: { whitelistSpot: {} },
+          name: "Free Whitelist Spot",
+          cost: 0,
+          supply: 2,
+        })
+        .accountsStrict({
+          admin: admin.publicKey,
+          config: configPda,
+          item: freeItem,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      const tx = await redeem(freeItem, 0);
+      console.log(`Redeem tx: ${tx}`);
+      await redeem(freeItem, 1);
+
+      const item = await program.account.redeemItem.fetch(freeItem);
+      assert.equal(item.redeemed, 2);
+      for (const index of [0, 1]) {
+        const redemption = await program.account.redemption.fetch(
+          redemptionAddress(freeItem, index)
+        );
+        assert.equal(redemption.user.toString(), user.publicKey.toString());
+        assert.equal(redemption.item.toString(), freeItem.toString());
+        assert.equal(redemption.pointsSpent, 0);
+        assert.isNull(redemption.asset);
+      }
+
+      try {
+        await redeem(freeItem, 2);
+        assert.fail("Redeem should stop at the item supply");
+      } catch (err) {
+        assert.include(err.toString(), "ItemSoldOut");
+      }
+    });
+  });
+
+  describe("Close User", () => {
+    it("Close the user account and reclaim rent", async () => {
+      const tx = await program.methods
+        .closeUser()
+        .accountsStrict({
+          user: user.publicKey,
+          userAccount: userAccountPda,
+          config: configPda,
+          rewardMint: rewardMintPda,
+          userRewardAta: null,
+          poolStats: poolStatsPda,
+          tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+
+      console.log(`Close User tx: ${tx}`);
+
+      const userAccount = await connection.getAccountInfo(userAccountPda);
+      assert.isNull(userAccount, "User account should be closed");
+
+      const poolStats = await program.account.poolStats.fetch(poolStatsPda);
+      assert.isFalse(
+        poolStats.leaderboard.some((entry) => entry.user.equals(user.publicKey)),
+        "User should be removed from the leaderboard"
+      );
+    });
+  });
 });
@@ -0,0 +1,198 @@
+#![no_std]
+
+// Pool math shared by the on-chain program and off-chain clients, so a quote
+// matches execution to the unit. Amounts are what reaches or leaves the vaults,
+// Token-2022 transfer fees and concentrated pools are handled by the program.
+
+mod stable_swap;
+
+pub use stable_swap::{stable_swap_d, stable_swap_y};
+
+// LP tokens locked forever by the first deposit
+pub const MINIMUM_LIQUIDITY: u64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteError {
+    Overflow,
+    Underflow,
+    ZeroBalance,
+    NoLiquidity,
+    InsufficientBalance,
+    InvalidFee,
+    BelowMinimumLiquidity,
+    NotConverged,
+    InvalidAmplification,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Curve {
+    ConstantProduct,
+    StableSwap { amp: u64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapQuote {
+    pub amount_in: u64,  // Input reaching the vault, fee included
+    pub amount_out: u64, // Output leaving the vault
+    pub fee: u64,        // Part of amount_in kept as the swap fee
+}
+
+// Exact-input swap of `amount_in`, the fee is taken from the input and rounded down
+pub fn quote_swap(
+    curve: Curve,
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+    fee_bps: u16,
+) -> Result<SwapQuote, QuoteError> {
+    if fee_bps > 10000 {
+        return Err(QuoteError::InvalidFee);
+    }
+
+    let fee = (amount_in as u128 * fee_bps as u128 / 10000) as u64;
+    let swap_in = (amount_in - fee) as u128;
+    let new_in = (reserve_in as u128)
+        .checked_add(swap_in)
+        .ok_or(QuoteError::Overflow)?;
+
+    let amount_out = match curve {
+        Curve::ConstantProduct => {
+            // K = X_vault * Y_vault, new_out = K / new_in rounded up so K never shrinks
+            let k = reserve_in as u128 * reserve_out as u128;
+            if new_in == 0 {
+                return Err(QuoteError::NoLiquidity);
+            }
+            let new_out = k.div_ceil(new_in);
+            (reserve_out as u128)
+                .checked_sub(new_out)
+                .ok_or(QuoteError::Underflow)?
+        }
+        Curve::StableSwap { amp } => {
+            let d = stable_swap_d(amp, reserve_in, reserve_out)?;
+            let new_out = stable_swap_y(amp, new_in, d)?;
+
+            // One unit kept back so rounding in the iteration never favours the swapper
+            (reserve_out as u128)
+                .checked_sub(new_out)
+                .and_then(|out| out.checked_sub(1))
+                .unwrap_or(0)
+        }
+    };
+
+    Ok(SwapQuote {
+        amount_in,
+        amount_out: amount_out.try_into().map_err(|_| QuoteError::Overflow)?,
+        fee,
+    })
+}
+
+// Exact-output swap, the input needed for `amount_out` rounded up
+pub fn quote_swap_exact_out(
+    curve: Curve,
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_out: u64,
+    fee_bps: u16,
+) -> Result<SwapQuote, QuoteError> {
+    if fee_bps >= 10000 {
+        return Err(QuoteError::InvalidFee);
+    }
+    if amount_out >= reserve_out {
+        return Err(QuoteError::InsufficientBalance);
+    }
+
+    let swap_in = match curve {
+        // Inverse of the constant product: swap_in = X * out / (Y - out), rounded up
+        Curve::ConstantProduct => (reserve_in as u128)
+            .checked_mul(amount_out as u128)
+            .ok_or(QuoteError::Overflow)?
+            .div_ceil((reserve_out - amount_out) as u128),
+        Curve::StableSwap { amp } => {
+            let d = stable_swap_d(amp, reserve_in, reserve_out)?;
+            let new_in = stable_swap_y(amp, (reserve_out - amount_out) as u128, d)?;
+            new_in
+                .checked_sub(reserve_in as u128)
+                .ok_or(QuoteError::Underflow)?
+                + 1
+        }
+    };
+
+    // Gross up for the fee: amount_in = swap_in * 10000 / (10000 - fee), rounded up
+    let amount_in: u64 = swap_in
+        .checked_mul(10000)
+        .ok_or(QuoteError::Overflow)?
+        .div_ceil(10000 - fee_bps as u128)
+        .try_into()
+        .map_err(|_| QuoteError::Overflow)?;
+    let fee = (amount_in as u128 * fee_bps as u128 / 10000) as u64;
+
+    Ok(SwapQuote {
+        amount_in,
+        amount_out,
+        fee,
+    })
+}
+
+// X and Y needed to mint `lp_amount`, rounded up in favour of the pool
+pub fn quote_deposit(
+    reserve_x: u64,
+    reserve_y: u64,
+    lp_supply: u64,
+    lp_amount: u64,
+) -> Result<(u64, u64), QuoteError> {
+    if lp_supply == 0 {
+        return Err(QuoteError::NoLiquidity);
+    }
+
+    let share = |reserve: u64| -> Result<u64, QuoteError> {
+        (lp_amount as u128 * reserve as u128)
+            .div_ceil(lp_supply as u128)
+            .try_into()
+            .map_err(|_| QuoteError::Overflow)
+    };
+    Ok((share(reserve_x)?, share(reserve_y)?))
+}
+
+// LP minted by the first deposit, sqrt(x * y) with MINIMUM_LIQUIDITY of it locked
+pub fn quote_initial_deposit(amount_x: u64, amount_y: u64) -> Result<u64, QuoteError> {
+    let liquidity = sqrt(amount_x as u128 * amount_y as u128) as u64;
+    if liquidity <= MINIMUM_LIQUIDITY {
+        return Err(QuoteError::BelowMinimumLiquidity);
+    }
+
+    Ok(liquidity - MINIMUM_LIQUIDITY)
+}
+
+// X and Y returned for burning `lp_amount`, rounded down in favour of the pool
+pub fn quote_withdraw(
+    reserve_x: u64,
+    reserve_y: u64,
+    lp_supply: u64,
+    lp_amount: u64,
+) -> Result<(u64, u64), QuoteError> {
+    if lp_amount > lp_supply {
+        return Err(QuoteError::InsufficientBalance);
+    }
+    if lp_supply == 0 {
+        return Err(QuoteError::NoLiquidity);
+    }
+
+    // Never more than the reserve, so the casts can't truncate
+    let share = |reserve: u64| (lp_amount as u128 * reserve as u128 / lp_supply as u128) as u64;
+    Ok((share(reserve_x), share(reserve_y)))
+}
+
+// Integer square root, rounded down
+pub fn sqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+
+    let mut x = value;
+    let mut y = (x + value / x) / 2;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
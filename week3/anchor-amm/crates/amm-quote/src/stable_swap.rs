@@ -0,0 +1,93 @@
+use crate::QuoteError;
+
+const MAX_ITERATIONS: usize = 255;
+
+// StableSwap invariant D for two balances: A*n^n*(x + y) + D = A*D*n^n + D^(n+1) / (n^n*x*y)
+pub fn stable_swap_d(amp: u64, x: u64, y: u64) -> Result<u128, QuoteError> {
+    if amp == 0 {
+        return Err(QuoteError::InvalidAmplification);
+    }
+    if x == 0 || y == 0 {
+        return Err(QuoteError::NoLiquidity);
+    }
+
+    let (x, y) = (x as u128, y as u128);
+    let sum = x + y;
+    let ann = (amp as u128) * 4;
+
+    let mut d = sum;
+    for _ in 0..MAX_ITERATIONS {
+        // D^3 / (4*x*y), one balance at a time to stay in range
+        let d_p = d
+            .checked_mul(d)
+            .ok_or(QuoteError::Overflow)?
+            .checked_div(x * 2)
+            .ok_or(QuoteError::Underflow)?
+            .checked_mul(d)
+            .ok_or(QuoteError::Overflow)?
+            .checked_div(y * 2)
+            .ok_or(QuoteError::Underflow)?;
+
+        let numerator = ann
+            .checked_mul(sum)
+            .and_then(|n| n.checked_add(d_p.checked_mul(2)?))
+            .and_then(|n| n.checked_mul(d))
+            .ok_or(QuoteError::Overflow)?;
+        let denominator = (ann - 1)
+            .checked_mul(d)
+            .and_then(|n| n.checked_add(d_p.checked_mul(3)?))
+            .ok_or(QuoteError::Overflow)?;
+
+        let d_prev = d;
+        d = numerator / denominator;
+        if d.abs_diff(d_prev) <= 1 {
+            return Ok(d);
+        }
+    }
+
+    Err(QuoteError::NotConverged)
+}
+
+// Balance of the other side that keeps the invariant at `d` once this side holds `x`
+pub fn stable_swap_y(amp: u64, x: u128, d: u128) -> Result<u128, QuoteError> {
+    if amp == 0 {
+        return Err(QuoteError::InvalidAmplification);
+    }
+    if x == 0 {
+        return Err(QuoteError::ZeroBalance);
+    }
+
+    let ann = (amp as u128) * 4;
+
+    // c = D^3 / (4*x*Ann), b = x + D/Ann
+    let c = d
+        .checked_mul(d)
+        .ok_or(QuoteError::Overflow)?
+        .checked_div(x * 2)
+        .ok_or(QuoteError::Underflow)?
+        .checked_mul(d)
+        .ok_or(QuoteError::Overflow)?
+        .checked_div(ann * 2)
+        .ok_or(QuoteError::Underflow)?;
+    let b = x.checked_add(d / ann).ok_or(QuoteError::Overflow)?;
+
+    let mut y = d;
+    for _ in 0..MAX_ITERATIONS {
+        let numerator = y
+            .checked_mul(y)
+            .and_then(|n| n.checked_add(c))
+            .ok_or(QuoteError::Overflow)?;
+        let denominator = (y * 2)
+            .checked_add(b)
+            .and_then(|n| n.checked_sub(d))
+            .ok_or(QuoteError::Underflow)?;
+
+        let y_prev = y;
+        y = numerator / denominator;
+        if y.abs_diff(y_prev) <= 1 {
+            return Ok(y);
+        }
+    }
+
+    Err(QuoteError::NotConverged)
+}
@@ -0,0 +1,191 @@
+use amm_quote::{
+    quote_deposit, quote_initial_deposit, quote_swap, quote_swap_exact_out, quote_withdraw,
+    stable_swap_d, stable_swap_y, Curve, QuoteError, MINIMUM_LIQUIDITY,
+};
+
+const RUNS: usize = 20_000;
+
+// xorshift64*, deterministic so a failing case can be replayed from its seed
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // Spread over magnitudes so dust and whale sized amounts both show up
+    fn amount(&mut self, max_bits: u32) -> u64 {
+        let bits = self.next() % max_bits as u64 + 1;
+        self.next() >> (64 - bits)
+    }
+
+    fn fee(&mut self) -> u16 {
+        match self.next() % 4 {
+            0 => 0,
+            _ => (self.next() % 1000) as u16,
+        }
+    }
+
+    // Stable pools are only meant for near-pegged balances, D loses precision far off peg
+    fn reserves(&mut self, curve: Curve) -> (u64, u64) {
+        let reserve_in = self.amount(48) + 1;
+        let reserve_out = match curve {
+            Curve::ConstantProduct => self.amount(48) + 1,
+            Curve::StableSwap { .. } => {
+                let ratio = self.next() % 10_000 + 1;
+                (reserve_in as u128 * ratio as u128 / 100).clamp(1, 1 << 48) as u64
+            }
+        };
+        (reserve_in, reserve_out + 1)
+    }
+
+    fn curve(&mut self) -> Curve {
+        match self.next() % 2 {
+            0 => Curve::ConstantProduct,
+            _ => Curve::StableSwap {
+                amp: self.next() % 1000 + 1,
+            },
+        }
+    }
+}
+
+// Pool reserves after a swap, as if the protocol took the whole fee out
+fn swapped(reserve_in: u64, reserve_out: u64, amount_in: u64, out: u64, fee: u64) -> (u64, u64) {
+    (reserve_in + amount_in - fee, reserve_out - out)
+}
+
+// None when D does not converge, that pool state just refuses further swaps
+fn invariant(curve: Curve, x: u64, y: u64) -> Option<u128> {
+    match curve {
+        Curve::ConstantProduct => Some(x as u128 * y as u128),
+        Curve::StableSwap { amp } => stable_swap_d(amp, x, y).ok(),
+    }
+}
+
+// D only converges to within one unit either way, so two readings can be two apart
+fn tolerance(curve: Curve) -> u128 {
+    match curve {
+        Curve::ConstantProduct => 0,
+        Curve::StableSwap { .. } => 2,
+    }
+}
+
+#[test]
+fn swaps_never_decrease_the_invariant() {
+    let mut rng = Rng(0x5eed_0001);
+    for _ in 0..RUNS {
+        let curve = rng.curve();
+        let (reserve_in, reserve_out) = rng.reserves(curve);
+        let amount_in = rng.amount(48);
+        let fee = rng.fee();
+
+        let Ok(quote) = quote_swap(curve, reserve_in, reserve_out, amount_in, fee) else {
+            continue;
+        };
+        assert!(quote.amount_out < reserve_out, "{curve:?} drained the pool");
+        let (new_in, new_out) = swapped(
+            reserve_in,
+            reserve_out,
+            amount_in,
+            quote.amount_out,
+            quote.fee,
+        );
+        let Some(after) = invariant(curve, new_in, new_out) else {
+            continue;
+        };
+        assert!(
+            after + tolerance(curve) >= invariant(curve, reserve_in, reserve_out).unwrap(),
+            "{curve:?} swap of {amount_in} at fee {fee} against ({reserve_in}, {reserve_out})"
+        );
+    }
+}
+
+#[test]
+fn exact_out_swaps_never_decrease_the_invariant() {
+    let mut rng = Rng(0x5eed_0002);
+    for _ in 0..RUNS {
+        let curve = rng.curve();
+        let (reserve_in, reserve_out) = rng.reserves(curve);
+        let amount_out = rng.amount(48) % reserve_out;
+        let fee = rng.fee();
+
+        let Ok(quote) = quote_swap_exact_out(curve, reserve_in, reserve_out, amount_out, fee)
+        else {
+            continue;
+        };
+        let (new_in, new_out) = swapped(
+            reserve_in,
+            reserve_out,
+            quote.amount_in,
+            amount_out,
+            quote.fee,
+        );
+        let Some(after) = invariant(curve, new_in, new_out) else {
+            continue;
+        };
+        assert!(
+            after + tolerance(curve) >= invariant(curve, reserve_in, reserve_out).unwrap(),
+            "{curve:?} exact out {amount_out} at fee {fee} against ({reserve_in}, {reserve_out})"
+        );
+
+        // Paying the quoted input through the exact-input path gives at least the output,
+        // stable pools keep a unit back on both paths so only the product is exact
+        if curve == Curve::ConstantProduct {
+            let forward = quote_swap(curve, reserve_in, reserve_out, quote.amount_in, fee).unwrap();
+            assert!(forward.amount_out >= amount_out);
+        }
+    }
+}
+
+#[test]
+fn liquidity_round_trips_never_dilute_lps() {
+    let mut rng = Rng(0x5eed_0003);
+    for _ in 0..RUNS {
+        let (amount_x, amount_y) = (rng.amount(40) + 1, rng.amount(40) + 1);
+        let Ok(user_lp) = quote_initial_deposit(amount_x, amount_y) else {
+            continue;
+        };
+        let (mut x, mut y, mut supply) = (amount_x, amount_y, user_lp + MINIMUM_LIQUIDITY);
+
+        for _ in 0..8 {
+            let lp_amount = rng.amount(40) % (supply * 2) + 1;
+            let (before_x, before_y, before_supply) = (x, y, supply);
+
+            match rng.next() % 2 {
+                0 => {
+                    let (dx, dy) = quote_deposit(x, y, supply, lp_amount).unwrap();
+                    (x, y, supply) = (x + dx, y + dy, supply + lp_amount);
+                }
+                _ => {
+                    // The locked minimum liquidity never leaves
+                    let lp_amount = lp_amount.min(supply - MINIMUM_LIQUIDITY);
+                    let (dx, dy) = quote_withdraw(x, y, supply, lp_amount).unwrap();
+                    (x, y, supply) = (x - dx, y - dy, supply - lp_amount);
+                }
+            }
+
+            // Value per LP token never drops: x / supply >= before_x / before_supply
+            assert!(x as u128 * before_supply as u128 >= before_x as u128 * supply as u128);
+            assert!(y as u128 * before_supply as u128 >= before_y as u128 * supply as u128);
+        }
+    }
+}
+
+#[test]
+fn zero_amplification_is_rejected() {
+    assert_eq!(
+        stable_swap_d(0, 1_000, 1_000),
+        Err(QuoteError::InvalidAmplification)
+    );
+    assert_eq!(
+        stable_swap_y(0, 1_000, 2_000),
+        Err(QuoteError::InvalidAmplification)
+    );
+    assert_eq!(
+        quote_swap(Curve::StableSwap { amp: 0 }, 1_000, 1_000, 100, 30),
+        Err(QuoteError::InvalidAmplification)
+    );
+}
@@ -0,0 +1,400 @@
+// Random deposit, swap and withdraw sequences run against the built program in an
+// in-process SVM. `anchor run fuzz` builds the program and runs them.
+
+use std::path::Path;
+
+use anchor_amm::CurveType;
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, program_option::COption, program_pack::Pack},
+    system_program, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address},
+    token::{
+        self,
+        spl_token::state::{Account as TokenAccount, AccountState, Mint},
+    },
+};
+use litesvm::LiteSVM;
+use solana_account::Account;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+#[allow(clippy::all)]
+mod u256 {
+    uint::construct_uint! {
+        pub struct U256(4);
+    }
+}
+use u256::U256;
+
+const PROGRAM: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../target/deploy/anchor_amm.so"
+);
+const SEQUENCES: u64 = 32;
+const STEPS: usize = 64;
+const USERS: usize = 3;
+const BALANCE: u64 = 1_000_000_000_000; // Of each token, per user
+
+// xorshift64*, deterministic so a failing sequence can be replayed from its seed
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // Spread over magnitudes so dust and whale sized amounts both show up
+    fn amount(&mut self, max_bits: u32) -> u64 {
+        let bits = self.next() % max_bits as u64 + 1;
+        self.next() >> (64 - bits)
+    }
+}
+
+struct Pool {
+    svm: LiteSVM,
+    users: Vec<Keypair>,
+    mint_x: Pubkey,
+    mint_y: Pubkey,
+    mint_lp: Pubkey,
+    config: Pubkey,
+    vault_x: Pubkey,
+    vault_y: Pubkey,
+}
+
+// Reserves and LP supply, with protocol fees off these are the vault balances
+#[derive(Clone, Copy, Debug)]
+struct State {
+    reserve_x: u64,
+    reserve_y: u64,
+    lp_supply: u64,
+}
+
+impl State {
+    fn k(&self) -> U256 {
+        U256::from(self.reserve_x) * U256::from(self.reserve_y)
+    }
+
+    // k / supply^2 is the squared value of one LP token, it may only grow
+    fn lp_value_at_least(&self, before: &State) -> bool {
+        let supply = U256::from(self.lp_supply);
+        let before_supply = U256::from(before.lp_supply);
+        self.k() * before_supply * before_supply >= before.k() * supply * supply
+    }
+}
+
+impl Pool {
+    fn new(seed: u64, fee: u16) -> Self {
+        let mut svm = LiteSVM::new();
+        svm.add_program_from_file(anchor_amm::ID, PROGRAM).unwrap();
+
+        let users: Vec<Keypair> = (0..USERS).map(|_| Keypair::new()).collect();
+        for user in &users {
+            svm.airdrop(&user.pubkey(), 100_000_000_000).unwrap();
+        }
+
+        let config = Pubkey::find_program_address(
+            &[b"config", seed.to_le_bytes().as_ref()],
+            &anchor_amm::ID,
+        )
+        .0;
+        let mint_lp = Pubkey::find_program_address(&[b"lp", config.as_ref()], &anchor_amm::ID).0;
+        let (mint_x, mint_y) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let mut pool = Pool {
+            svm,
+            users,
+            mint_x,
+            mint_y,
+            mint_lp,
+            config,
+            vault_x: get_associated_token_address(&config, &mint_x),
+            vault_y: get_associated_token_address(&config, &mint_y),
+        };
+        for mint in [mint_x, mint_y] {
+            pool.set_mint(mint, BALANCE * USERS as u64);
+            for user in 0..USERS {
+                let owner = pool.users[user].pubkey();
+                pool.set_token_account(owner, mint, BALANCE);
+            }
+        }
+
+        let initializer = pool.users[0].pubkey();
+        let ix = Instruction {
+            program_id: anchor_amm::ID,
+            accounts: anchor_amm::accounts::Initialize {
+                initializer,
+                mint_x,
+                mint_y,
+                mint_lp,
+                vault_x: pool.vault_x,
+                vault_y: pool.vault_y,
+                config,
+                token_program: token::ID,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_amm::instruction::Initialize {
+                seed,
+                fee,
+                authority: None,
+                curve: CurveType::ConstantProduct,
+                amp: 0,
+            }
+            .data(),
+        };
+        assert!(pool.send(0, ix), "initialize failed");
+
+        pool
+    }
+
+    fn set_mint(&mut self, address: Pubkey, supply: u64) {
+        let mut data = vec![0; Mint::LEN];
+        let mint = Mint {
+            mint_authority: COption::None,
+            supply,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        Mint::pack(mint, &mut data).unwrap();
+        self.set_token_program_account(address, data);
+    }
+
+    fn set_token_account(&mut self, owner: Pubkey, mint: Pubkey, amount: u64) {
+        let mut data = vec![0; TokenAccount::LEN];
+        let account = TokenAccount {
+            mint,
+            owner,
+            amount,
+            delegate: COption::None,
+            state: AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        };
+        TokenAccount::pack(account, &mut data).unwrap();
+        self.set_token_program_account(get_associated_token_address(&owner, &mint), data);
+    }
+
+    fn set_token_program_account(&mut self, address: Pubkey, data: Vec<u8>) {
+        let account = Account {
+            lamports: self.svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: token::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.svm.set_account(address, account).unwrap();
+    }
+
+    fn token_amount(&self, address: &Pubkey) -> u64 {
+        match self.svm.get_account(address) {
+            Some(account) if !account.data.is_empty() => {
+                TokenAccount::unpack(&account.data).unwrap().amount
+            }
+            _ => 0,
+        }
+    }
+
+    fn state(&self) -> State {
+        let mint_lp = self.svm.get_account(&self.mint_lp).unwrap();
+        State {
+            reserve_x: self.token_amount(&self.vault_x),
+            reserve_y: self.token_amount(&self.vault_y),
+            lp_supply: Mint::unpack(&mint_lp.data).unwrap().supply,
+        }
+    }
+
+    fn user_lp(&self, user: usize) -> u64 {
+        self.token_amount(&get_associated_token_address(
+            &self.users[user].pubkey(),
+            &self.mint_lp,
+        ))
+    }
+
+    // Whether the transaction landed, failing is fine but panicking never is
+    fn send(&mut self, user: usize, ix: Instruction) -> bool {
+        let signer = &self.users[user];
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&signer.pubkey()),
+            &[signer],
+            self.svm.latest_blockhash(),
+        );
+        let result = self.svm.send_transaction(tx);
+        // Repeated actions would otherwise be rejected as already processed
+        self.svm.expire_blockhash();
+
+        let (landed, logs) = match result {
+            Ok(meta) => (true, meta.logs),
+            Err(failed) => (false, failed.meta.logs),
+        };
+        assert!(
+            !logs.iter().any(|log| log.contains("panicked")),
+            "instruction panicked:\n{}",
+            logs.join("\n")
+        );
+        landed
+    }
+
+    fn deposit(&mut self, user: usize, amount: u64, max_x: u64, max_y: u64) -> bool {
+        let owner = self.users[user].pubkey();
+        let ix = Instruction {
+            program_id: anchor_amm::ID,
+            accounts: anchor_amm::accounts::Deposit {
+                user: owner,
+                mint_x: self.mint_x,
+                mint_y: self.mint_y,
+                config: self.config,
+                mint_lp: self.mint_lp,
+                vault_x: self.vault_x,
+                vault_y: self.vault_y,
+                user_x: get_associated_token_address(&owner, &self.mint_x),
+                user_y: get_associated_token_address(&owner, &self.mint_y),
+                user_lp: get_associated_token_address(&owner, &self.mint_lp),
+                locked_lp: get_associated_token_address(&self.config, &self.mint_lp),
+                oracle: None,
+                token_program: token::ID,
+                system_program: system_program::ID,
+                associated_token_program: associated_token::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_amm::instruction::Deposit {
+                amount,
+                max_x,
+                max_y,
+            }
+            .data(),
+        };
+        self.send(user, ix)
+    }
+
+    fn swap(&mut self, user: usize, is_x: bool, amount_in: u64) -> bool {
+        let owner = self.users[user].pubkey();
+        let ix = Instruction {
+            program_id: anchor_amm::ID,
+            accounts: anchor_amm::accounts::Swap {
+                swapper: owner,
+                mint_x: self.mint_x,
+                mint_y: self.mint_y,
+                config: self.config,
+                vault_x: self.vault_x,
+                vault_y: self.vault_y,
+                user_x: get_associated_token_address(&owner, &self.mint_x),
+                user_y: get_associated_token_address(&owner, &self.mint_y),
+                oracle: None,
+                ticks: None,
+                token_program: token::ID,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_amm::instruction::Swap {
+                is_x,
+                amount_in,
+                min_amount_out: 0,
+            }
+            .data(),
+        };
+        self.send(user, ix)
+    }
+
+    fn withdraw(&mut self, user: usize, amount: u64) -> bool {
+        let owner = self.users[user].pubkey();
+        let ix = Instruction {
+            program_id: anchor_amm::ID,
+            accounts: anchor_amm::accounts::Withdraw {
+                withdrawer: owner,
+                mint_x: self.mint_x,
+                mint_y: self.mint_y,
+                config: self.config,
+                vault_x: self.vault_x,
+                vault_y: self.vault_y,
+                mint_lp: self.mint_lp,
+                user_lp: get_associated_token_address(&owner, &self.mint_lp),
+                user_x: get_associated_token_address(&owner, &self.mint_x),
+                user_y: get_associated_token_address(&owner, &self.mint_y),
+                oracle: None,
+                token_program: token::ID,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: anchor_amm::instruction::Withdraw {
+                amount,
+                max_x: 0, // Minimums, named max_ in the instruction
+                max_y: 0,
+            }
+            .data(),
+        };
+        self.send(user, ix)
+    }
+}
+
+#[test]
+fn random_sequences_keep_the_pool_invariants() {
+    assert!(
+        Path::new(PROGRAM).exists(),
+        "{PROGRAM} not found, run `anchor run fuzz` to build the program first"
+    );
+
+    for seed in 0..SEQUENCES {
+        let mut rng = Rng(0x5eed_0000 + seed);
+        // Zero fee pools included, k and LP value must hold without fees to lean on
+        let fee = match seed % 4 {
+            0 => 0,
+            _ => (rng.next() % 1000) as u16,
+        };
+        let mut pool = Pool::new(seed, fee);
+
+        let (amount_x, amount_y) = (rng.amount(40) + 1_000_000, rng.amount(40) + 1_000_000);
+        assert!(
+            pool.deposit(0, 1, amount_x, amount_y),
+            "seed {seed}: first deposit failed"
+        );
+
+        let mut swaps = 0;
+        for step in 0..STEPS {
+            let user = (rng.next() % USERS as u64) as usize;
+            let before = pool.state();
+
+            match rng.next() % 3 {
+                0 => {
+                    let supply = before.lp_supply;
+                    let amount = rng.amount(40) % supply + 1;
+                    pool.deposit(user, amount, u64::MAX, u64::MAX);
+                }
+                1 => {
+                    let is_x = rng.next().is_multiple_of(2);
+                    if pool.swap(user, is_x, rng.amount(40)) {
+                        swaps += 1;
+                        assert!(
+                            pool.state().k() >= before.k(),
+                            "seed {seed} step {step}: swap decreased k, {before:?} to {:?}",
+                            pool.state()
+                        );
+                    }
+                }
+                _ => {
+                    let amount = rng.amount(40) % (pool.user_lp(user) + 1);
+                    pool.withdraw(user, amount);
+                }
+            }
+
+            let after = pool.state();
+            assert!(
+                after.lp_value_at_least(&before),
+                "seed {seed} step {step}: LP value dropped, {before:?} to {after:?}"
+            );
+        }
+        assert!(swaps > 0, "seed {seed}: no swap ever landed");
+    }
+}
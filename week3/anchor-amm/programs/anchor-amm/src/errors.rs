@@ -1,5 +1,5 @@
+use amm_quote::QuoteError;
 use anchor_lang::error_code;
-use constant_product_curve::CurveError;
 
 #[error_code]
 pub enum AmmError {
@@ -39,18 +39,62 @@ pub enum AmmError {
     InsufficientBalance,
     #[msg("Zero balance.")]
     ZeroBalance,
+    #[msg("Invalid swap route.")]
+    InvalidRoute,
+    #[msg("No oracle observation old enough for this window.")]
+    OracleWindowTooLong,
+    #[msg("This pool's oracle must be passed to record the price update.")]
+    MissingOracle,
+    #[msg("Invalid amplification coefficient.")]
+    InvalidAmplification,
+    #[msg("Invalid amplification ramp.")]
+    InvalidRamp,
+    #[msg("Pool does not use the StableSwap curve.")]
+    NotStableSwap,
+    #[msg("Operation not supported by this pool's curve.")]
+    UnsupportedCurve,
+    #[msg("Invalid tick.")]
+    InvalidTick,
+    #[msg("Invalid price.")]
+    InvalidPrice,
+    #[msg("Concentrated pools need their tick list account.")]
+    MissingTicks,
+    #[msg("Too many initialized ticks in this pool.")]
+    TooManyTicks,
+    #[msg("Position still holds liquidity or fees.")]
+    PositionNotEmpty,
+    #[msg("Invalid dynamic fee window.")]
+    InvalidFeeWindow,
+    #[msg("A flash loan is already outstanding.")]
+    FlashLoanActive,
+    #[msg("Flash loan has no flash_repay later in the transaction.")]
+    FlashLoanNotRepaid,
+    #[msg("No flash loan to repay.")]
+    NoFlashLoan,
+    #[msg("Pair mints must be sorted.")]
+    UnsortedMints,
+    #[msg("Fee tier is not enabled in the factory.")]
+    InvalidFeeTier,
+    #[msg("Too many fee tiers in the factory.")]
+    TooManyFeeTiers,
+    #[msg("Pool is not at the canonical seed for its pair, or has a foreign authority.")]
+    NonCanonicalPool,
+    #[msg("Both mints must use the same token program.")]
+    MixedTokenPrograms,
 }
 
-impl From<CurveError> for AmmError {
-    fn from(error: CurveError) -> AmmError {
+impl From<QuoteError> for AmmError {
+    fn from(error: QuoteError) -> AmmError {
         match error {
-            CurveError::InvalidPrecision => AmmError::InvalidPrecision,
-            CurveError::Overflow => AmmError::Overflow,
-            CurveError::Underflow => AmmError::Underflow,
-            CurveError::InvalidFeeAmount => AmmError::InvalidFee,
-            CurveError::InsufficientBalance => AmmError::InsufficientBalance,
-            CurveError::ZeroBalance => AmmError::ZeroBalance,
-            CurveError::SlippageLimitExceeded => AmmError::SlippageExceeded,
+            QuoteError::Overflow => AmmError::Overflow,
+            QuoteError::Underflow => AmmError::Underflow,
+            QuoteError::ZeroBalance => AmmError::ZeroBalance,
+            QuoteError::NoLiquidity => AmmError::NoLiquidityInPool,
+            QuoteError::InsufficientBalance => AmmError::InsufficientBalance,
+            QuoteError::InvalidFee => AmmError::InvalidFee,
+            QuoteError::BelowMinimumLiquidity => AmmError::LiquidityLessThanMinimum,
+            QuoteError::NotConverged => AmmError::CurveError,
+            QuoteError::InvalidAmplification => AmmError::InvalidAmplification,
         }
     }
 }
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::state::Config;
+
+#[event]
+pub struct Swap {
+    pub config: Pubkey,
+    pub user: Pubkey,
+    pub is_x: bool,      // X in and Y out, otherwise the reverse
+    pub amount_in: u64,  // Sent by the user, swap fee included
+    pub amount_out: u64, // Sent from the vault
+    pub fee: u64,        // Swap fee taken from the input, protocol share included
+    pub reserve_x: u64,  // Reserves after the swap
+    pub reserve_y: u64,
+}
+
+#[event]
+pub struct LiquidityAdded {
+    pub config: Pubkey,
+    pub user: Pubkey,
+    pub amount_x: u64, // Sent by the user
+    pub amount_y: u64, // Sent by the user
+    pub lp_amount: u64,
+    pub reserve_x: u64, // Reserves after the deposit
+    pub reserve_y: u64,
+    pub lp_supply: u64, // LP supply after the deposit
+}
+
+#[event]
+pub struct LiquidityRemoved {
+    pub config: Pubkey,
+    pub user: Pubkey,
+    pub amount_x: u64, // Sent from the vault
+    pub amount_y: u64, // Sent from the vault
+    pub lp_amount: u64,
+    pub reserve_x: u64, // Reserves after the withdrawal
+    pub reserve_y: u64,
+    pub lp_supply: u64, // LP supply after the withdrawal
+}
+
+// Emitted whenever the reserves change
+#[event]
+pub struct Sync {
+    pub config: Pubkey,
+    pub reserve_x: u64,
+    pub reserve_y: u64,
+}
+
+// Reserves once this instruction's transfers have landed, announced with a Sync event
+pub fn sync(
+    config: &Account<Config>,
+    vault_x: &mut InterfaceAccount<TokenAccount>,
+    vault_y: &mut InterfaceAccount<TokenAccount>,
+) -> Result<(u64, u64)> {
+    vault_x.reload()?;
+    vault_y.reload()?;
+    let (reserve_x, reserve_y) = config.reserves(vault_x.amount, vault_y.amount)?;
+
+    emit!(Sync {
+        config: config.key(),
+        reserve_x,
+        reserve_y,
+    });
+    Ok((reserve_x, reserve_y))
+}
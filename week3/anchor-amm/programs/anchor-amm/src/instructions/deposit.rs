@@ -1,19 +1,27 @@
+use amm_quote::{quote_deposit, quote_initial_deposit, MINIMUM_LIQUIDITY};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{mint_to, transfer, Mint, MintTo, Token, TokenAccount, Transfer},
+    token_interface::{
+        mint_to, transfer_checked, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+    },
 };
-use constant_product_curve::ConstantProduct;
 
-use crate::{errors::AmmError, state::Config};
+use crate::{
+    errors::AmmError,
+    events::{sync, LiquidityAdded},
+    state::{record_observation, Config, CurveType, Oracle},
+    utils::{amount_after_fee, amount_with_fee},
+};
 
 #[derive(Accounts)]
 pub struct Deposit<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
-    pub mint_x: Account<'info, Mint>,
-    pub mint_y: Account<'info, Mint>,
+    pub mint_x: InterfaceAccount<'info, Mint>,
+    pub mint_y: InterfaceAccount<'info, Mint>,
     #[account(
+        mut,
         has_one = mint_x,
         has_one = mint_y,
         seeds = [b"config", config.seed.to_le_bytes().as_ref()],
@@ -25,39 +33,58 @@ pub struct Deposit<'info> {
         seeds = [b"lp", config.key().as_ref()],
         bump = config.lp_bump,
     )]
-    pub mint_lp: Account<'info, Mint>,
+    pub mint_lp: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
         associated_token::mint = mint_x,
         associated_token::authority = config,
+        associated_token::token_program = token_program,
     )]
-    pub vault_x: Account<'info, TokenAccount>,
+    pub vault_x: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
         associated_token::mint = mint_y,
         associated_token::authority = config,
+        associated_token::token_program = token_program,
     )]
-    pub vault_y: Account<'info, TokenAccount>,
+    pub vault_y: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
         associated_token::mint = mint_x,
         associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_x: Account<'info, TokenAccount>,
+    pub user_x: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
         associated_token::mint = mint_y,
         associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub user_y: Account<'info, TokenAccount>,
+    pub user_y: InterfaceAccount<'info, TokenAccount>,
     #[account(
         init_if_needed,
         payer = user,
         associated_token::mint = mint_lp,
         associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_lp: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_lp,
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
     )]
-    pub user_lp: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+    pub locked_lp: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"oracle", config.key().as_ref()],
+        bump = oracle.bump,
+    )]
+    pub oracle: Option<Account<'info, Oracle>>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
@@ -65,30 +92,47 @@ pub struct Deposit<'info> {
 impl<'info> Deposit<'info> {
     pub fn deposit(
         &mut self,
-        amount: u64, // Amount of LP tokens that the user wants to "claim"
+        amount: u64, // Amount of LP tokens to "claim", the minimum accepted on the first deposit
         max_x: u64,  // Maximum amount of token X that the user is willing to deposit
         max_y: u64,  // Maximum amount of token Y that the user is willing to deposit
     ) -> Result<()> {
-        require!(self.config.locked == false, AmmError::PoolLocked);
+        require!(!self.config.locked, AmmError::PoolLocked);
+        // Concentrated liquidity goes through positions instead of the LP mint
+        require!(
+            self.config.curve != CurveType::Concentrated,
+            AmmError::UnsupportedCurve
+        );
         require!(amount != 0, AmmError::InvalidAmount);
 
-        let (x, y) = match self.mint_lp.supply == 0
-            && self.vault_x.amount == 0
-            && self.vault_y.amount == 0
-        {
-            true => (max_x, max_y),
-            false => {
-                let amounts = ConstantProduct::xy_deposit_amounts_from_l(
-                    self.vault_x.amount,
-                    self.vault_y.amount,
-                    self.mint_lp.supply,
-                    amount,
-                    6,
-                )
-                .unwrap();
-                (amounts.x, amounts.y)
-            }
-        };
+        let (reserve_x, reserve_y) = self
+            .config
+            .reserves(self.vault_x.amount, self.vault_y.amount)?;
+
+        self.config
+            .update_price_cumulative(reserve_x, reserve_y, Clock::get()?.unix_timestamp);
+        record_observation(&self.config, self.oracle.as_deref_mut())?;
+
+        // The first deposit sets the LP price at sqrt(x * y), so the amounts can't skew it
+        if self.mint_lp.supply == 0 {
+            // Priced on what reaches the vaults after any Token-2022 transfer fee
+            let net_x = amount_after_fee(&self.mint_x, max_x)?;
+            let net_y = amount_after_fee(&self.mint_y, max_y)?;
+            let user_liquidity = quote_initial_deposit(net_x, net_y).map_err(AmmError::from)?;
+            require!(user_liquidity >= amount, AmmError::SlippageExceeded);
+
+            self.deposit_tokens(true, max_x)?;
+            self.deposit_tokens(false, max_y)?;
+            self.mint_lp_tokens(self.locked_lp.to_account_info(), MINIMUM_LIQUIDITY)?;
+            self.mint_lp_tokens(self.user_lp.to_account_info(), user_liquidity)?;
+            return self.emit_liquidity_added(max_x, max_y, user_liquidity);
+        }
+
+        // Rounded up, so minting LP never costs less than its share of the pool
+        let (x, y) = quote_deposit(reserve_x, reserve_y, self.mint_lp.supply, amount)
+            .map_err(AmmError::from)?;
+        // The vaults need x and y after fees, the user sends them grossed up
+        let x = amount_with_fee(&self.mint_x, x)?;
+        let y = amount_with_fee(&self.mint_y, y)?;
 
         require!(x <= max_x && y <= max_y, AmmError::SlippageExceeded); // This is an important filter for lp_tokens check
 
@@ -97,40 +141,64 @@ impl<'info> Deposit<'info> {
         // deposit token y
         self.deposit_tokens(false, y)?;
         // mint lp tokens
-        self.mint_lp_tokens(amount)
+        self.mint_lp_tokens(self.user_lp.to_account_info(), amount)?;
+
+        self.emit_liquidity_added(x, y, amount)
+    }
+
+    fn emit_liquidity_added(&mut self, amount_x: u64, amount_y: u64, lp_amount: u64) -> Result<()> {
+        let (reserve_x, reserve_y) = sync(&self.config, &mut self.vault_x, &mut self.vault_y)?;
+        self.mint_lp.reload()?;
+
+        emit!(LiquidityAdded {
+            config: self.config.key(),
+            user: self.user.key(),
+            amount_x,
+            amount_y,
+            lp_amount,
+            reserve_x,
+            reserve_y,
+            lp_supply: self.mint_lp.supply,
+        });
+        Ok(())
     }
 
     pub fn deposit_tokens(&self, is_x: bool, amount: u64) -> Result<()> {
-        let (from, to) = match is_x {
+        let (from, to, mint, decimals) = match is_x {
             true => (
                 self.user_x.to_account_info(),
                 self.vault_x.to_account_info(),
+                self.mint_x.to_account_info(),
+                self.mint_x.decimals,
             ),
             false => (
                 self.user_y.to_account_info(),
                 self.vault_y.to_account_info(),
+                self.mint_y.to_account_info(),
+                self.mint_y.decimals,
             ),
         };
 
         let cpi_program = self.token_program.to_account_info();
 
-        let cpi_accounts = Transfer {
+        let cpi_accounts = TransferChecked {
             from,
+            mint,
             to,
             authority: self.user.to_account_info(),
         };
 
         let ctx = CpiContext::new(cpi_program, cpi_accounts);
 
-        transfer(ctx, amount)
+        transfer_checked(ctx, amount, decimals)
     }
 
-    pub fn mint_lp_tokens(&self, amount: u64) -> Result<()> {
+    pub fn mint_lp_tokens(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
         let cpi_program = self.token_program.to_account_info();
 
         let cpi_accounts = MintTo {
             mint: self.mint_lp.to_account_info(),
-            to: self.user_lp.to_account_info(),
+            to,
             authority: self.config.to_account_info(),
         };
 
@@ -0,0 +1,241 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::sysvar::{
+        self,
+        instructions::{load_current_index_checked, load_instruction_at_checked},
+    },
+    Discriminator,
+};
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{
+    errors::AmmError,
+    events::sync,
+    instruction::FlashRepay,
+    state::{Config, CurveType},
+    utils::amount_with_fee,
+};
+
+// Position of `config` in the accounts of a flash_repay instruction
+const REPAY_CONFIG_INDEX: usize = 3;
+
+#[derive(Accounts)]
+pub struct FlashLoan<'info> {
+    pub borrower: Signer<'info>,
+
+    pub mint_x: InterfaceAccount<'info, Mint>,
+    pub mint_y: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = mint_x,
+        has_one = mint_y,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_x,
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_x: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_y,
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_y: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint_x,
+        token::token_program = token_program,
+    )]
+    pub borrower_x: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint_y,
+        token::token_program = token_program,
+    )]
+    pub borrower_y: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: The instruction sysvar, pinned by address
+    #[account(
+        address = sysvar::instructions::ID,
+    )]
+    pub instruction_sysvar: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> FlashLoan<'info> {
+    pub fn flash_loan(&mut self, amount_x: u64, amount_y: u64) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        // Concentrated fees belong to the positions in range, not the whole vault
+        require!(
+            self.config.curve != CurveType::Concentrated,
+            AmmError::UnsupportedCurve
+        );
+        require!(amount_x != 0 || amount_y != 0, AmmError::InvalidAmount);
+        require!(
+            self.config.flash_debt_x == 0 && self.config.flash_debt_y == 0,
+            AmmError::FlashLoanActive
+        );
+
+        let (reserve_x, reserve_y) = self
+            .config
+            .reserves(self.vault_x.amount, self.vault_y.amount)?;
+        require!(
+            amount_x <= reserve_x && amount_y <= reserve_y,
+            AmmError::InsufficientBalance
+        );
+        self.check_repay()?;
+
+        // The fee is fixed now, a dynamic fee could move before the repay
+        self.config.flash_debt_x = self.borrow(true, amount_x)?;
+        self.config.flash_debt_y = self.borrow(false, amount_y)?;
+
+        self.withdraw_tokens(true, amount_x)?;
+        self.withdraw_tokens(false, amount_y)
+    }
+
+    pub fn flash_repay(&mut self) -> Result<()> {
+        let (debt_x, debt_y) = (self.config.flash_debt_x, self.config.flash_debt_y);
+        require!(debt_x != 0 || debt_y != 0, AmmError::NoFlashLoan);
+
+        self.config.flash_debt_x = 0;
+        self.config.flash_debt_y = 0;
+
+        // The vaults need the full debt after Token-2022 transfer fees
+        let amount_x = amount_with_fee(&self.mint_x, debt_x)?;
+        let amount_y = amount_with_fee(&self.mint_y, debt_y)?;
+        self.deposit_tokens(true, amount_x)?;
+        self.deposit_tokens(false, amount_y)?;
+
+        // The flash fee grew the reserves
+        sync(&self.config, &mut self.vault_x, &mut self.vault_y)?;
+        Ok(())
+    }
+
+    // A flash_repay for this pool has to follow in the same transaction
+    fn check_repay(&self) -> Result<()> {
+        let instructions = self.instruction_sysvar.to_account_info();
+        let mut index = load_current_index_checked(&instructions)? as usize + 1;
+
+        while let Ok(ix) = load_instruction_at_checked(index, &instructions) {
+            if ix.program_id == crate::ID
+                && ix.data.starts_with(FlashRepay::DISCRIMINATOR)
+                && ix
+                    .accounts
+                    .get(REPAY_CONFIG_INDEX)
+                    .is_some_and(|account| account.pubkey == self.config.key())
+            {
+                return Ok(());
+            }
+            index += 1;
+        }
+
+        err!(AmmError::FlashLoanNotRepaid)
+    }
+
+    // Debt for lending `amount`, the pool swap fee on top rounded up
+    fn borrow(&mut self, is_x: bool, amount: u64) -> Result<u64> {
+        if amount == 0 {
+            return Ok(0);
+        }
+
+        let fee_amount: u64 = (amount as u128)
+            .checked_mul(self.config.base_fee() as u128)
+            .ok_or(AmmError::Overflow)?
+            .div_ceil(10000)
+            .try_into()
+            .map_err(|_| AmmError::Overflow)?;
+        self.config.accrue_protocol_fee(is_x, fee_amount)?;
+
+        amount
+            .checked_add(fee_amount)
+            .ok_or(AmmError::Overflow.into())
+    }
+
+    fn deposit_tokens(&self, is_x: bool, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let (from, to, mint, decimals) = match is_x {
+            true => (
+                &self.borrower_x,
+                &self.vault_x,
+                &self.mint_x,
+                self.mint_x.decimals,
+            ),
+            false => (
+                &self.borrower_y,
+                &self.vault_y,
+                &self.mint_y,
+                self.mint_y.decimals,
+            ),
+        };
+
+        let transfer_account = TransferChecked {
+            from: from.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: self.borrower.to_account_info(),
+        };
+        transfer_checked(
+            CpiContext::new(self.token_program.to_account_info(), transfer_account),
+            amount,
+            decimals,
+        )
+    }
+
+    fn withdraw_tokens(&self, is_x: bool, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let (from, to, mint, decimals) = match is_x {
+            true => (
+                &self.vault_x,
+                &self.borrower_x,
+                &self.mint_x,
+                self.mint_x.decimals,
+            ),
+            false => (
+                &self.vault_y,
+                &self.borrower_y,
+                &self.mint_y,
+                self.mint_y.decimals,
+            ),
+        };
+
+        let transfer_account = TransferChecked {
+            from: from.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: self.config.to_account_info(),
+        };
+        let config_seeds = self.config.seed.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] =
+            &[&[b"config", config_seeds.as_ref(), &[self.config.config_bump]]];
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                transfer_account,
+                signer_seeds,
+            ),
+            amount,
+            decimals,
+        )
+    }
+}
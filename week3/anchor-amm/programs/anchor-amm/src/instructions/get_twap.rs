@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::{Config, Oracle};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct Twap {
+    pub price_x: u128, // Q64.64 price of X in Y
+    pub price_y: u128, // Q64.64 price of Y in X
+}
+
+#[derive(Accounts)]
+pub struct GetTwap<'info> {
+    pub mint_x: InterfaceAccount<'info, Mint>,
+    pub mint_y: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        has_one = mint_x,
+        has_one = mint_y,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [b"oracle", config.key().as_ref()],
+        bump = oracle.bump,
+    )]
+    pub oracle: Account<'info, Oracle>,
+
+    #[account(
+        associated_token::mint = mint_x,
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_x: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        associated_token::mint = mint_y,
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_y: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> GetTwap<'info> {
+    pub fn get_twap(&self, window: i64) -> Result<Twap> {
+        let now = Clock::get()?.unix_timestamp;
+        let (reserve_x, reserve_y) = self
+            .config
+            .reserves(self.vault_x.amount, self.vault_y.amount)?;
+        let (price_x_cumulative, price_y_cumulative) =
+            self.config.price_cumulative_at(reserve_x, reserve_y, now);
+
+        let (price_x, price_y) =
+            self.oracle
+                .twap(price_x_cumulative, price_y_cumulative, now, window)?;
+
+        Ok(Twap { price_x, price_y })
+    }
+}
@@ -1,18 +1,21 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-use crate::state::Config;
+use crate::{
+    errors::AmmError,
+    state::{Config, CurveType, DynamicFee, Oracle, MAX_AMP},
+};
 
 #[derive(Accounts)]
 #[instruction(seed: u64)]
 pub struct Initialize<'info> {
     #[account(mut)]
     pub initializer: Signer<'info>,
-    pub mint_x: Account<'info, Mint>,
-    pub mint_y: Account<'info, Mint>,
+    pub mint_x: InterfaceAccount<'info, Mint>,
+    pub mint_y: InterfaceAccount<'info, Mint>,
     #[account(
         init,
         payer = initializer,
@@ -20,22 +23,30 @@ pub struct Initialize<'info> {
         bump,
         mint::decimals = 6,
         mint::authority = config,
+        mint::token_program = token_program,
+        // One token program holds the vaults and the LP mint, so pairs mixing Token and
+        // Token-2022 aren't supported. Checked on the first account created, before the vaults.
+        constraint = mint_x.to_account_info().owner == token_program.key
+            && mint_y.to_account_info().owner == token_program.key
+            @ AmmError::MixedTokenPrograms,
     )]
-    pub mint_lp: Account<'info, Mint>,
+    pub mint_lp: InterfaceAccount<'info, Mint>,
     #[account(
         init,
         payer = initializer,
         associated_token::mint = mint_x,
         associated_token::authority = config,
+        associated_token::token_program = token_program,
     )]
-    pub vault_x: Account<'info, TokenAccount>,
+    pub vault_x: InterfaceAccount<'info, TokenAccount>,
     #[account(
         init,
         payer = initializer,
         associated_token::mint = mint_y,
         associated_token::authority = config,
+        associated_token::token_program = token_program,
     )]
-    pub vault_y: Account<'info, TokenAccount>,
+    pub vault_y: InterfaceAccount<'info, TokenAccount>,
     #[account(
         init,
         payer = initializer,
@@ -44,7 +55,16 @@ pub struct Initialize<'info> {
         space = Config::DISCRIMINATOR.len() + Config::INIT_SPACE,
     )]
     pub config: Account<'info, Config>,
-    pub token_program: Program<'info, Token>,
+    // Optional, the only way a pool without an authority can get an oracle
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [b"oracle", config.key().as_ref()],
+        bump,
+        space = Oracle::DISCRIMINATOR.len() + Oracle::INIT_SPACE,
+    )]
+    pub oracle: Option<Account<'info, Oracle>>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
@@ -55,19 +75,56 @@ impl<'info> Initialize<'info> {
         seed: u64,
         fee: u16,
         authority: Option<Pubkey>,
+        curve: CurveType,
+        amp: u64,
         bumps: InitializeBumps,
     ) -> Result<()> {
+        require!(fee < 10000, AmmError::InvalidFee);
+        let amp = match curve {
+            CurveType::ConstantProduct | CurveType::Concentrated => 0,
+            CurveType::StableSwap => {
+                require!((1..=MAX_AMP).contains(&amp), AmmError::InvalidAmplification);
+                amp
+            }
+        };
+        let now = Clock::get()?.unix_timestamp;
+
         self.config.set_inner(Config {
             seed,
             authority,
             mint_x: self.mint_x.key(),
             mint_y: self.mint_y.key(),
             fee,
+            dynamic_fee: DynamicFee::default(),
+            protocol_fee: 0,
+            protocol_fees_x: 0,
+            protocol_fees_y: 0,
+            flash_debt_x: 0,
+            flash_debt_y: 0,
             locked: false,
+            curve,
+            amp_initial: amp,
+            amp_target: amp,
+            amp_ramp_start: now,
+            amp_ramp_stop: now,
+            tick_spacing: 0,
+            sqrt_price: 0,
+            tick_current: 0,
+            liquidity: 0,
+            fee_growth_global_x: 0,
+            fee_growth_global_y: 0,
+            price_x_cumulative: 0,
+            price_y_cumulative: 0,
+            last_update: now,
+            has_oracle: false,
             config_bump: bumps.config,
             lp_bump: bumps.mint_lp,
         });
 
+        if let (Some(oracle), Some(bump)) = (self.oracle.as_mut(), bumps.oracle) {
+            oracle.initialize(self.config.key(), &mut self.config, bump);
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::AmmError,
+    state::{tick_at_sqrt_price, Config, CurveType, TickList, MAX_TICK_SPACING},
+};
+
+#[derive(Accounts)]
+pub struct InitializeConcentrated<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"ticks", config.key().as_ref()],
+        bump,
+        space = TickList::DISCRIMINATOR.len() + TickList::INIT_SPACE,
+    )]
+    pub ticks: Account<'info, TickList>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeConcentrated<'info> {
+    pub fn initialize_concentrated(
+        &mut self,
+        tick_spacing: u16,
+        sqrt_price: u128,
+        bumps: InitializeConcentratedBumps,
+    ) -> Result<()> {
+        require!(
+            self.config.curve == CurveType::Concentrated,
+            AmmError::UnsupportedCurve
+        );
+        // Pools with an authority only let it pick the starting price
+        if let Some(authority) = self.config.authority {
+            require_keys_eq!(authority, self.payer.key(), AmmError::InvalidAuthority);
+        }
+        require!(
+            (1..=MAX_TICK_SPACING).contains(&tick_spacing),
+            AmmError::InvalidTick
+        );
+        require!(self.config.fee < 10000, AmmError::InvalidFee);
+
+        self.config.tick_spacing = tick_spacing;
+        self.config.sqrt_price = sqrt_price;
+        self.config.tick_current = tick_at_sqrt_price(sqrt_price)?;
+
+        self.ticks.set_inner(TickList {
+            config: self.config.key(),
+            ticks: Vec::new(),
+            bump: bumps.ticks,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::AmmError, program::AnchorAmm, state::Factory};
+
+#[derive(Accounts)]
+pub struct InitializeFactory<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // The factory is a singleton, so only the program's upgrade authority may create it
+    #[account(constraint = this_program.programdata_address()? == Some(program_data.key()))]
+    pub this_program: Program<'info, AnchorAmm>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ AmmError::InvalidAuthority,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"factory"],
+        bump,
+        space = Factory::DISCRIMINATOR.len() + Factory::INIT_SPACE,
+    )]
+    pub factory: Account<'info, Factory>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeFactory<'info> {
+    pub fn initialize_factory(
+        &mut self,
+        fee_tiers: Vec<u16>,
+        bumps: InitializeFactoryBumps,
+    ) -> Result<()> {
+        self.factory.set_inner(Factory {
+            authority: self.authority.key(),
+            fee_tiers: Vec::new(),
+            pool_count: 0,
+            bump: bumps.factory,
+        });
+        for fee_tier in fee_tiers {
+            self.factory.add_fee_tier(fee_tier)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct UpdateFactory<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority @ AmmError::InvalidAuthority,
+        seeds = [b"factory"],
+        bump = factory.bump,
+    )]
+    pub factory: Account<'info, Factory>,
+}
+
+impl<'info> UpdateFactory<'info> {
+    pub fn add_fee_tier(&mut self, fee_tier: u16) -> Result<()> {
+        self.factory.add_fee_tier(fee_tier)
+    }
+}
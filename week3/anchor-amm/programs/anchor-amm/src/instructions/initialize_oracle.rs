@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::AmmError,
+    state::{Config, Oracle},
+};
+
+// Once a pool has an oracle every swap must pass it, so only the pool authority can add one.
+// Pools without an authority can only get theirs at initialize.
+#[derive(Accounts)]
+pub struct InitializeOracle<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"oracle", config.key().as_ref()],
+        bump,
+        space = Oracle::DISCRIMINATOR.len() + Oracle::INIT_SPACE,
+    )]
+    pub oracle: Account<'info, Oracle>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeOracle<'info> {
+    pub fn initialize_oracle(&mut self, bumps: InitializeOracleBumps) -> Result<()> {
+        let authority = self.config.authority.ok_or(AmmError::NoAuthoritySet)?;
+        require_keys_eq!(authority, self.authority.key(), AmmError::InvalidAuthority);
+
+        let config_key = self.config.key();
+        self.oracle
+            .initialize(config_key, &mut self.config, bumps.oracle);
+
+        Ok(())
+    }
+}
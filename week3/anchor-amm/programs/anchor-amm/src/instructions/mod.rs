@@ -1,9 +1,35 @@
+pub mod close_position;
+pub mod collect_protocol_fees;
 pub mod deposit;
+pub mod flash_loan;
+pub mod get_twap;
 pub mod initialize;
+pub mod initialize_concentrated;
+pub mod initialize_factory;
+pub mod initialize_oracle;
+pub mod modify_position;
+pub mod open_position;
+pub mod register_pool;
+pub mod route_swap;
 pub mod swap;
+pub mod update;
 pub mod withdraw;
+pub mod zap;
 
+pub use close_position::*;
+pub use collect_protocol_fees::*;
 pub use deposit::*;
+pub use flash_loan::*;
+pub use get_twap::*;
 pub use initialize::*;
+pub use initialize_concentrated::*;
+pub use initialize_factory::*;
+pub use initialize_oracle::*;
+pub use modify_position::*;
+pub use open_position::*;
+pub use register_pool::*;
+pub use route_swap::*;
 pub use swap::*;
+pub use update::*;
 pub use withdraw::*;
+pub use zap::*;
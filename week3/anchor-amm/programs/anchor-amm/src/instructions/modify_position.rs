@@ -0,0 +1,269 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{
+    errors::AmmError,
+    state::{Config, Position, TickList},
+    utils::{amount_after_fee, amount_with_fee},
+};
+
+#[derive(Accounts)]
+pub struct ModifyPosition<'info> {
+    pub owner: Signer<'info>,
+
+    pub mint_x: InterfaceAccount<'info, Mint>,
+    pub mint_y: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = mint_x,
+        has_one = mint_y,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"ticks", config.key().as_ref()],
+        bump = ticks.bump,
+    )]
+    pub ticks: Account<'info, TickList>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = config,
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_x,
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_x: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_y,
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_y: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint_x,
+        token::token_program = token_program,
+    )]
+    pub user_x: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint_y,
+        token::token_program = token_program,
+    )]
+    pub user_y: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> ModifyPosition<'info> {
+    pub fn increase_liquidity(&mut self, liquidity: u128, max_x: u64, max_y: u64) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(
+            liquidity != 0 && liquidity <= i128::MAX as u128,
+            AmmError::InvalidAmount
+        );
+
+        // Ticks first, a new tick has to exist before the fees inside can be read
+        self.update_ticks(liquidity as i128)?;
+        let fee_growth_inside = self.fee_growth_inside()?;
+        self.position.update(liquidity as i128, fee_growth_inside)?;
+        self.update_active_liquidity(liquidity as i128)?;
+
+        let (x, y) = self.config.liquidity_amounts(
+            self.position.tick_lower,
+            self.position.tick_upper,
+            liquidity,
+            true,
+        )?;
+        // The vaults need x and y after Token-2022 transfer fees
+        let x = amount_with_fee(&self.mint_x, x)?;
+        let y = amount_with_fee(&self.mint_y, y)?;
+        require!(x <= max_x && y <= max_y, AmmError::SlippageExceeded);
+
+        self.deposit_tokens(true, x)?;
+        self.deposit_tokens(false, y)
+    }
+
+    pub fn decrease_liquidity(&mut self, liquidity: u128, min_x: u64, min_y: u64) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(
+            liquidity != 0 && liquidity <= self.position.liquidity,
+            AmmError::InvalidAmount
+        );
+
+        // Fees before the ticks, emptied ticks are removed
+        let fee_growth_inside = self.fee_growth_inside()?;
+        self.position
+            .update(-(liquidity as i128), fee_growth_inside)?;
+        self.update_ticks(-(liquidity as i128))?;
+        self.update_active_liquidity(-(liquidity as i128))?;
+
+        let (x, y) = self.config.liquidity_amounts(
+            self.position.tick_lower,
+            self.position.tick_upper,
+            liquidity,
+            false,
+        )?;
+        require!(
+            amount_after_fee(&self.mint_x, x)? >= min_x
+                && amount_after_fee(&self.mint_y, y)? >= min_y,
+            AmmError::SlippageExceeded
+        );
+
+        self.withdraw_tokens(true, x)?;
+        self.withdraw_tokens(false, y)
+    }
+
+    pub fn collect_fees(&mut self) -> Result<()> {
+        if self.position.liquidity > 0 {
+            let fee_growth_inside = self.fee_growth_inside()?;
+            self.position.update(0, fee_growth_inside)?;
+        }
+
+        let (x, y) = (self.position.fees_owed_x, self.position.fees_owed_y);
+        self.position.fees_owed_x = 0;
+        self.position.fees_owed_y = 0;
+
+        self.withdraw_tokens(true, x)?;
+        self.withdraw_tokens(false, y)
+    }
+
+    fn update_ticks(&mut self, liquidity_delta: i128) -> Result<()> {
+        let fee_growth_global = (
+            self.config.fee_growth_global_x,
+            self.config.fee_growth_global_y,
+        );
+
+        self.ticks.update(
+            self.position.tick_lower,
+            liquidity_delta,
+            false,
+            self.config.tick_current,
+            fee_growth_global,
+        )?;
+        self.ticks.update(
+            self.position.tick_upper,
+            liquidity_delta,
+            true,
+            self.config.tick_current,
+            fee_growth_global,
+        )
+    }
+
+    fn fee_growth_inside(&self) -> Result<(u128, u128)> {
+        self.ticks.fee_growth_inside(
+            self.position.tick_lower,
+            self.position.tick_upper,
+            self.config.tick_current,
+            (
+                self.config.fee_growth_global_x,
+                self.config.fee_growth_global_y,
+            ),
+        )
+    }
+
+    // Only positions around the current price trade
+    fn update_active_liquidity(&mut self, liquidity_delta: i128) -> Result<()> {
+        let tick_current = self.config.tick_current;
+        if tick_current >= self.position.tick_lower && tick_current < self.position.tick_upper {
+            self.config.liquidity = self
+                .config
+                .liquidity
+                .checked_add_signed(liquidity_delta)
+                .ok_or(AmmError::Overflow)?;
+        }
+
+        Ok(())
+    }
+
+    fn deposit_tokens(&self, is_x: bool, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let (from, to, mint, decimals) = match is_x {
+            true => (
+                &self.user_x,
+                &self.vault_x,
+                &self.mint_x,
+                self.mint_x.decimals,
+            ),
+            false => (
+                &self.user_y,
+                &self.vault_y,
+                &self.mint_y,
+                self.mint_y.decimals,
+            ),
+        };
+
+        let transfer_account = TransferChecked {
+            from: from.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: self.owner.to_account_info(),
+        };
+        transfer_checked(
+            CpiContext::new(self.token_program.to_account_info(), transfer_account),
+            amount,
+            decimals,
+        )
+    }
+
+    fn withdraw_tokens(&self, is_x: bool, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let (from, to, mint, decimals) = match is_x {
+            true => (
+                &self.vault_x,
+                &self.user_x,
+                &self.mint_x,
+                self.mint_x.decimals,
+            ),
+            false => (
+                &self.vault_y,
+                &self.user_y,
+                &self.mint_y,
+                self.mint_y.decimals,
+            ),
+        };
+
+        let transfer_account = TransferChecked {
+            from: from.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: self.config.to_account_info(),
+        };
+        let config_seeds = self.config.seed.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] =
+            &[&[b"config", config_seeds.as_ref(), &[self.config.config_bump]]];
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                transfer_account,
+                signer_seeds,
+            ),
+            amount,
+            decimals,
+        )
+    }
+}
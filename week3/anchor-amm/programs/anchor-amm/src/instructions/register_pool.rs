@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    errors::AmmError,
+    state::{Config, Factory, Pair},
+};
+
+#[derive(Accounts)]
+#[instruction(fee_tier: u16)]
+pub struct RegisterPool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // Lets the factory authority register a pool that isn't at the canonical seed
+    pub authority: Option<Signer<'info>>,
+
+    #[account(
+        mut,
+        seeds = [b"factory"],
+        bump = factory.bump,
+    )]
+    pub factory: Account<'info, Factory>,
+
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    pub mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    // A second pool for the same pair and tier fails here, the first one registered wins
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            b"pair",
+            mint_a.key().as_ref(),
+            mint_b.key().as_ref(),
+            fee_tier.to_le_bytes().as_ref(),
+        ],
+        bump,
+        space = Pair::DISCRIMINATOR.len() + Pair::INIT_SPACE,
+    )]
+    pub pair: Account<'info, Pair>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RegisterPool<'info> {
+    pub fn register_pool(&mut self, fee_tier: u16, bumps: RegisterPoolBumps) -> Result<()> {
+        let (mint_a, mint_b) = (self.mint_a.key(), self.mint_b.key());
+        require!(mint_a < mint_b, AmmError::UnsortedMints);
+        require!(
+            (self.config.mint_x == mint_a && self.config.mint_y == mint_b)
+                || (self.config.mint_x == mint_b && self.config.mint_y == mint_a),
+            AmmError::InvalidToken
+        );
+
+        // Dynamic fee pools register under their base fee
+        let fee = self.config.base_fee();
+        require!(
+            fee == fee_tier && self.factory.fee_tiers.contains(&fee_tier),
+            AmmError::InvalidFeeTier
+        );
+
+        // Anyone can register the pool at the canonical seed, as long as nobody but the
+        // factory authority controls it, any other pool needs the factory authority
+        let canonical = self.config.seed == Pair::canonical_seed(&mint_a, &mint_b, fee_tier)
+            && self
+                .config
+                .authority
+                .is_none_or(|authority| authority == self.factory.authority);
+        let by_factory = self
+            .authority
+            .as_ref()
+            .is_some_and(|authority| authority.key() == self.factory.authority);
+        require!(canonical || by_factory, AmmError::NonCanonicalPool);
+
+        self.pair.set_inner(Pair {
+            mint_a,
+            mint_b,
+            fee_tier,
+            config: self.config.key(),
+            bump: bumps.pair,
+        });
+        self.factory.pool_count = self
+            .factory
+            .pool_count
+            .checked_add(1)
+            .ok_or(AmmError::Overflow)?;
+
+        Ok(())
+    }
+}
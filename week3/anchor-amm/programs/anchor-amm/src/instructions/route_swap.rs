@@ -0,0 +1,252 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    errors::AmmError,
+    events::{sync, Swap},
+    state::{record_observation, Config, Oracle},
+    utils::amount_after_fee,
+};
+
+// Each hop takes [config, mint_in, mint_out, vault_in, vault_out, oracle] from the remaining
+// accounts, the oracle slot can hold any account for pools without one
+pub const HOP_ACCOUNTS: usize = 6;
+pub const MAX_HOPS: usize = 4;
+
+#[derive(Accounts)]
+pub struct RouteSwap<'info> {
+    #[account(mut)]
+    pub swapper: Signer<'info>,
+
+    #[account(
+        mut,
+        token::authority = swapper,
+        token::token_program = token_program,
+    )]
+    pub user_in: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::authority = swapper,
+        token::token_program = token_program,
+    )]
+    pub user_out: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+struct Hop<'info> {
+    config: Account<'info, Config>,
+    mint_in: InterfaceAccount<'info, Mint>,
+    mint_out: InterfaceAccount<'info, Mint>,
+    vault_in: InterfaceAccount<'info, TokenAccount>,
+    vault_out: InterfaceAccount<'info, TokenAccount>,
+    oracle: Option<Account<'info, Oracle>>,
+    is_x: bool,
+    amount_in: u64,
+    amount_out: u64,
+    fee: u64,
+}
+
+impl<'info> RouteSwap<'info> {
+    pub fn route_swap(
+        &mut self,
+        remaining_accounts: &'info [AccountInfo<'info>],
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        require!(amount_in != 0, AmmError::InvalidAmount);
+        require!(
+            !remaining_accounts.is_empty() && remaining_accounts.len() % HOP_ACCOUNTS == 0,
+            AmmError::InvalidRoute
+        );
+        require!(
+            remaining_accounts.len() / HOP_ACCOUNTS <= MAX_HOPS,
+            AmmError::InvalidRoute
+        );
+
+        // Quote every hop against the reserves before any tokens move
+        let now = Clock::get()?.unix_timestamp;
+        let mut hops: Vec<Hop<'info>> = Vec::with_capacity(MAX_HOPS);
+        let mut amount = amount_in;
+        let mut mint = self.user_in.mint;
+        for accounts in remaining_accounts.chunks(HOP_ACCOUNTS) {
+            let mut hop = self.load_hop(accounts, mint)?;
+            require!(
+                hops.iter()
+                    .all(|other| other.config.key() != hop.config.key()),
+                AmmError::InvalidRoute
+            );
+
+            let is_x = hop.mint_in.key() == hop.config.mint_x;
+            let (vault_x_amount, vault_y_amount) = match is_x {
+                true => (hop.vault_in.amount, hop.vault_out.amount),
+                false => (hop.vault_out.amount, hop.vault_in.amount),
+            };
+            let (reserve_x, reserve_y) = hop.config.reserves(vault_x_amount, vault_y_amount)?;
+            hop.config
+                .update_price_cumulative(reserve_x, reserve_y, now);
+            record_observation(&hop.config, hop.oracle.as_deref_mut())?;
+            let (reserve_in, reserve_out) = match is_x {
+                true => (reserve_x, reserve_y),
+                false => (reserve_y, reserve_x),
+            };
+
+            // `amount` was sent into this pool, the curve sees it after any transfer fee
+            let amount_in = amount_after_fee(&hop.mint_in, amount)?;
+            let fee = hop
+                .config
+                .fee_for(is_x, reserve_in, reserve_out, amount_in, now)?;
+            hop.config
+                .record_fee_move(is_x, reserve_in, reserve_out, now);
+            let (swap_out, fee_amount) =
+                hop.config
+                    .swap_out(reserve_in, reserve_out, amount_in, fee, now)?;
+            require!(swap_out < reserve_out, AmmError::InsufficientBalance);
+            hop.config.accrue_protocol_fee(is_x, fee_amount)?;
+            hop.is_x = is_x;
+            hop.amount_in = amount;
+            hop.amount_out = swap_out;
+            hop.fee = fee_amount;
+
+            amount = swap_out;
+            mint = hop.mint_out.key();
+            hops.push(hop);
+        }
+        require_keys_eq!(self.user_out.mint, mint, AmmError::InvalidRoute);
+
+        // Slippage is only checked on the final output, as the swapper receives it
+        let amount_out = amount_after_fee(&hops[hops.len() - 1].mint_out, amount)?;
+        require!(amount_out >= min_amount_out, AmmError::SlippageExceeded);
+
+        transfer_checked(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.user_in.to_account_info(),
+                    mint: hops[0].mint_in.to_account_info(),
+                    to: hops[0].vault_in.to_account_info(),
+                    authority: self.swapper.to_account_info(),
+                },
+            ),
+            amount_in,
+            hops[0].mint_in.decimals,
+        )?;
+
+        // Each pool pays its output straight into the next pool's vault
+        for (i, hop) in hops.iter().enumerate() {
+            let to = match hops.get(i + 1) {
+                Some(next) => next.vault_in.to_account_info(),
+                None => self.user_out.to_account_info(),
+            };
+
+            let config_seeds = hop.config.seed.to_le_bytes();
+            let signer_seeds: &[&[&[u8]]] =
+                &[&[b"config", config_seeds.as_ref(), &[hop.config.config_bump]]];
+
+            transfer_checked(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    TransferChecked {
+                        from: hop.vault_out.to_account_info(),
+                        mint: hop.mint_out.to_account_info(),
+                        to,
+                        authority: hop.config.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                hop.amount_out,
+                hop.mint_out.decimals,
+            )?;
+
+            hop.config.exit(&crate::ID)?;
+            if let Some(oracle) = &hop.oracle {
+                oracle.exit(&crate::ID)?;
+            }
+        }
+
+        for hop in hops.iter_mut() {
+            let (vault_x, vault_y) = match hop.is_x {
+                true => (&mut hop.vault_in, &mut hop.vault_out),
+                false => (&mut hop.vault_out, &mut hop.vault_in),
+            };
+            let (reserve_x, reserve_y) = sync(&hop.config, vault_x, vault_y)?;
+
+            emit!(Swap {
+                config: hop.config.key(),
+                user: self.swapper.key(),
+                is_x: hop.is_x,
+                amount_in: hop.amount_in,
+                amount_out: hop.amount_out,
+                fee: hop.fee,
+                reserve_x,
+                reserve_y,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn load_hop(&self, accounts: &'info [AccountInfo<'info>], mint: Pubkey) -> Result<Hop<'info>> {
+        // Only this program can create accounts with the Config discriminator
+        let config = Account::<Config>::try_from(&accounts[0])?;
+        let mint_in = InterfaceAccount::<Mint>::try_from(&accounts[1])?;
+        let mint_out = InterfaceAccount::<Mint>::try_from(&accounts[2])?;
+        let vault_in = InterfaceAccount::<TokenAccount>::try_from(&accounts[3])?;
+        let vault_out = InterfaceAccount::<TokenAccount>::try_from(&accounts[4])?;
+
+        require!(!config.locked, AmmError::PoolLocked);
+
+        let oracle = match config.has_oracle {
+            true => {
+                let oracle = Account::<Oracle>::try_from(&accounts[5])?;
+                require_keys_eq!(oracle.config, config.key(), AmmError::MissingOracle);
+                Some(oracle)
+            }
+            false => None,
+        };
+
+        require_keys_eq!(mint_in.key(), mint, AmmError::InvalidRoute);
+        require!(
+            (mint_in.key() == config.mint_x && mint_out.key() == config.mint_y)
+                || (mint_in.key() == config.mint_y && mint_out.key() == config.mint_x),
+            AmmError::InvalidToken
+        );
+
+        let token_program = self.token_program.key();
+        require_keys_eq!(
+            vault_in.key(),
+            get_associated_token_address_with_program_id(
+                &config.key(),
+                &mint_in.key(),
+                &token_program
+            ),
+            AmmError::InvalidToken
+        );
+        require_keys_eq!(
+            vault_out.key(),
+            get_associated_token_address_with_program_id(
+                &config.key(),
+                &mint_out.key(),
+                &token_program
+            ),
+            AmmError::InvalidToken
+        );
+
+        Ok(Hop {
+            config,
+            mint_in,
+            mint_out,
+            vault_in,
+            vault_out,
+            oracle,
+            is_x: false,
+            amount_in: 0,
+            amount_out: 0,
+            fee: 0,
+        })
+    }
+}
@@ -3,9 +3,13 @@ use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
-use constant_product_curve::{ConstantProduct, LiquidityPair};
 
-use crate::{errors::AmmError, state::Config};
+use crate::{
+    errors::AmmError,
+    events::{self, sync},
+    state::{record_observation, Config, CurveType, Oracle, TickList},
+    utils::{amount_after_fee, amount_with_fee},
+};
 
 #[derive(Accounts)]
 pub struct Swap<'info> {
@@ -17,6 +21,8 @@ pub struct Swap<'info> {
 
     #[account(
         mut,
+        has_one = mint_x @ AmmError::InvalidToken,
+        has_one = mint_y @ AmmError::InvalidToken,
         seeds = [b"config", config.seed.to_le_bytes().as_ref()],
         bump = config.config_bump,
     )]
@@ -55,6 +61,20 @@ pub struct Swap<'info> {
     )]
     pub user_y: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        mut,
+        seeds = [b"oracle", config.key().as_ref()],
+        bump = oracle.bump,
+    )]
+    pub oracle: Option<Account<'info, Oracle>>,
+
+    #[account(
+        mut,
+        seeds = [b"ticks", config.key().as_ref()],
+        bump = ticks.bump,
+    )]
+    pub ticks: Option<Account<'info, TickList>>,
+
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -62,52 +82,135 @@ pub struct Swap<'info> {
 
 impl<'info> Swap<'info> {
     pub fn swap(&mut self, is_x: bool, amount: u64, min: u64) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
         // TODO
         // Swap Direction
+        let (reserve_x, reserve_y) = self
+            .config
+            .reserves(self.vault_x.amount, self.vault_y.amount)?;
         let (to_vault_amount, from_vault_amount) = match is_x {
-            true => (self.vault_x.amount, self.vault_y.amount),
-            false => (self.vault_y.amount, self.vault_x.amount),
+            true => (reserve_x, reserve_y),
+            false => (reserve_y, reserve_x),
         };
 
-        // Deduct the fee from the calculation
-        let fee_amount = (amount as u128)
-            .checked_mul(self.config.fee as u128)
-            .ok_or(AmmError::Overflow)?
-            .checked_div(10000)
-            .unwrap() as u64;
-        let swap_in = (amount as u128)
-            .checked_sub(fee_amount as u128)
-            .ok_or(AmmError::Underflow)? as u64;
-
-        // Constant product and swap tokens calculate
-        // K = X_vault * Y_vault
-        // new_X = X + Amount
-        // new_Y = K/new_X
-        let k = (to_vault_amount as u128)
-            .checked_mul(from_vault_amount as u128)
-            .ok_or(AmmError::Overflow)?;
-
-        let new_x = (to_vault_amount as u128)
-            .checked_add(swap_in as u128)
-            .ok_or(AmmError::Overflow)? as u64;
-
-        let new_y = k.checked_div(new_x as u128).ok_or(AmmError::Underflow)? as u64;
-
-        let swap_out = (from_vault_amount)
-            .checked_sub(new_y)
-            .ok_or(AmmError::Overflow)?;
+        let now = Clock::get()?.unix_timestamp;
+        self.config
+            .update_price_cumulative(reserve_x, reserve_y, now);
+        record_observation(&self.config, self.oracle.as_deref_mut())?;
 
-        // slippage tolerance limit check
-        require!(swap_out >= min, AmmError::SlippageExceeded);
+        // The curve only sees what reaches the vault after Token-2022 transfer fees
+        let (mint_in, mint_out) = match is_x {
+            true => (&self.mint_x, &self.mint_y),
+            false => (&self.mint_y, &self.mint_x),
+        };
+        let net_in = amount_after_fee(mint_in, amount)?;
+
+        // A concentrated swap can stop early at the edge of the liquidity
+        let (net_used, swap_out, fee_amount) = match self.config.curve {
+            CurveType::Concentrated => {
+                let ticks = self.ticks.as_mut().ok_or(AmmError::MissingTicks)?;
+                self.config.swap_concentrated(ticks, is_x, net_in)?
+            }
+            _ => {
+                let fee =
+                    self.config
+                        .fee_for(is_x, to_vault_amount, from_vault_amount, net_in, now)?;
+                self.config
+                    .record_fee_move(is_x, to_vault_amount, from_vault_amount, now);
+                let (swap_out, fee_amount) =
+                    self.config
+                        .swap_out(to_vault_amount, from_vault_amount, net_in, fee, now)?;
+                self.config.accrue_protocol_fee(is_x, fee_amount)?;
+                (net_in, swap_out, fee_amount)
+            }
+        };
+        let amount_in = match net_used == net_in {
+            true => amount,
+            false => amount_with_fee(mint_in, net_used)?,
+        };
+
+        // slippage tolerance limit check, on what the swapper actually receives
+        require!(
+            amount_after_fee(mint_out, swap_out)? >= min,
+            AmmError::SlippageExceeded
+        );
 
         // vault balance check (Note:Should actually have been vault_balance - rent_exempt )
         require!(swap_out <= from_vault_amount, AmmError::InsufficientBalance);
 
-        self.deposit_tokens(is_x, amount)?;
+        self.deposit_tokens(is_x, amount_in)?;
 
         self.withdraw_tokens(is_x, swap_out)?;
 
-        // method calls
+        self.emit_swap(is_x, amount_in, swap_out, fee_amount)
+    }
+
+    pub fn swap_exact_out(
+        &mut self,
+        is_x: bool,
+        amount_out: u64,
+        max_amount_in: u64,
+    ) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(amount_out != 0, AmmError::InvalidAmount);
+
+        let (reserve_x, reserve_y) = self
+            .config
+            .reserves(self.vault_x.amount, self.vault_y.amount)?;
+        let (to_vault_amount, from_vault_amount) = match is_x {
+            true => (reserve_x, reserve_y),
+            false => (reserve_y, reserve_x),
+        };
+
+        let now = Clock::get()?.unix_timestamp;
+        self.config
+            .update_price_cumulative(reserve_x, reserve_y, now);
+        record_observation(&self.config, self.oracle.as_deref_mut())?;
+
+        // The vault sends enough for `amount_out` to arrive after transfer fees
+        let (mint_in, mint_out) = match is_x {
+            true => (&self.mint_x, &self.mint_y),
+            false => (&self.mint_y, &self.mint_x),
+        };
+        let vault_out = amount_with_fee(mint_out, amount_out)?;
+
+        let fee =
+            self.config
+                .fee_for_out(is_x, to_vault_amount, from_vault_amount, vault_out, now)?;
+        self.config
+            .record_fee_move(is_x, to_vault_amount, from_vault_amount, now);
+        let (amount_in, fee_amount) =
+            self.config
+                .swap_in(to_vault_amount, from_vault_amount, vault_out, fee, now)?;
+
+        // The swapper sends enough for `amount_in` to reach the vault
+        let amount_in = amount_with_fee(mint_in, amount_in)?;
+
+        // slippage tolerance limit check
+        require!(amount_in <= max_amount_in, AmmError::SlippageExceeded);
+
+        self.deposit_tokens(is_x, amount_in)?;
+
+        self.withdraw_tokens(is_x, vault_out)?;
+
+        self.config.accrue_protocol_fee(is_x, fee_amount)?;
+
+        self.emit_swap(is_x, amount_in, vault_out, fee_amount)
+    }
+
+    fn emit_swap(&mut self, is_x: bool, amount_in: u64, amount_out: u64, fee: u64) -> Result<()> {
+        let (reserve_x, reserve_y) = sync(&self.config, &mut self.vault_x, &mut self.vault_y)?;
+
+        emit!(events::Swap {
+            config: self.config.key(),
+            user: self.swapper.key(),
+            is_x,
+            amount_in,
+            amount_out,
+            fee,
+            reserve_x,
+            reserve_y,
+        });
         Ok(())
     }
 
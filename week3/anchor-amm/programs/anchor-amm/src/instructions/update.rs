@@ -0,0 +1,155 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::AmmError,
+    state::{Config, CurveType, DynamicFee, MAX_AMP, MAX_AMP_CHANGE, MIN_RAMP_DURATION},
+};
+
+#[derive(Accounts)]
+pub struct Update<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> Update<'info> {
+    pub fn lock(&mut self) -> Result<()> {
+        self.check_authority()?;
+        self.config.locked = true;
+
+        Ok(())
+    }
+
+    pub fn unlock(&mut self) -> Result<()> {
+        self.check_authority()?;
+        self.config.locked = false;
+
+        Ok(())
+    }
+
+    pub fn transfer_authority(&mut self, new_authority: Pubkey) -> Result<()> {
+        self.check_authority()?;
+        self.config.authority = Some(new_authority);
+
+        Ok(())
+    }
+
+    pub fn set_protocol_fee(&mut self, protocol_fee: u16) -> Result<()> {
+        self.check_authority()?;
+        require!(protocol_fee <= 10000, AmmError::InvalidFee);
+        self.config.protocol_fee = protocol_fee;
+
+        Ok(())
+    }
+
+    pub fn enable_dynamic_fee(
+        &mut self,
+        base_fee: u16,
+        max_fee: u16,
+        sensitivity: u16,
+        window: i64,
+    ) -> Result<()> {
+        self.check_authority()?;
+        // A concentrated swap's price move is only known after crossing its ticks
+        require!(
+            self.config.curve != CurveType::Concentrated,
+            AmmError::UnsupportedCurve
+        );
+        require!(base_fee <= max_fee && max_fee < 10000, AmmError::InvalidFee);
+        require!(window > 0, AmmError::InvalidFeeWindow);
+
+        // The first swap sets the reference price
+        self.config.dynamic_fee = DynamicFee {
+            enabled: true,
+            base_fee,
+            max_fee,
+            sensitivity,
+            window,
+            reference_price: 0,
+            window_start: 0,
+        };
+
+        Ok(())
+    }
+
+    pub fn disable_dynamic_fee(&mut self) -> Result<()> {
+        self.check_authority()?;
+        // Swaps go back to the static fee, which enabling left untouched
+        self.config.dynamic_fee = DynamicFee::default();
+
+        Ok(())
+    }
+
+    pub fn ramp_amplification(&mut self, target_amp: u64, ramp_stop: i64) -> Result<()> {
+        self.check_authority()?;
+        require!(
+            self.config.curve == CurveType::StableSwap,
+            AmmError::NotStableSwap
+        );
+        require!(
+            (1..=MAX_AMP).contains(&target_amp),
+            AmmError::InvalidAmplification
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= self.config.amp_ramp_stop, AmmError::InvalidRamp);
+        require!(
+            ramp_stop >= now.saturating_add(MIN_RAMP_DURATION),
+            AmmError::InvalidRamp
+        );
+
+        // Bound how far a single ramp can move the curve
+        let current_amp = self.config.amplification(now);
+        require!(
+            target_amp <= current_amp.saturating_mul(MAX_AMP_CHANGE)
+                && target_amp.saturating_mul(MAX_AMP_CHANGE) >= current_amp,
+            AmmError::InvalidAmplification
+        );
+
+        self.config.amp_initial = current_amp;
+        self.config.amp_target = target_amp;
+        self.config.amp_ramp_start = now;
+        self.config.amp_ramp_stop = ramp_stop;
+
+        Ok(())
+    }
+
+    pub fn stop_ramp_amplification(&mut self) -> Result<()> {
+        self.check_authority()?;
+        require!(
+            self.config.curve == CurveType::StableSwap,
+            AmmError::NotStableSwap
+        );
+
+        // Freeze the amplification where the ramp currently is
+        let now = Clock::get()?.unix_timestamp;
+        let current_amp = self.config.amplification(now);
+        self.config.amp_initial = current_amp;
+        self.config.amp_target = current_amp;
+        self.config.amp_ramp_start = now;
+        self.config.amp_ramp_stop = now;
+
+        Ok(())
+    }
+
+    pub fn renounce_authority(&mut self) -> Result<()> {
+        self.check_authority()?;
+        // Nobody could unlock the pool afterwards
+        require!(!self.config.locked, AmmError::PoolLocked);
+        self.config.authority = None;
+
+        Ok(())
+    }
+
+    fn check_authority(&self) -> Result<()> {
+        let authority = self.config.authority.ok_or(AmmError::NoAuthoritySet)?;
+        require_keys_eq!(authority, self.authority.key(), AmmError::InvalidAuthority);
+
+        Ok(())
+    }
+}
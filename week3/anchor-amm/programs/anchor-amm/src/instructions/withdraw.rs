@@ -1,3 +1,4 @@
+use amm_quote::quote_withdraw;
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -6,9 +7,13 @@ use anchor_spl::{
         TransferChecked,
     },
 };
-use constant_product_curve::ConstantProduct;
 
-use crate::{errors::AmmError, state::Config};
+use crate::{
+    errors::AmmError,
+    events::{sync, LiquidityRemoved},
+    state::{record_observation, Config, CurveType, Oracle},
+    utils::amount_after_fee,
+};
 
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
@@ -20,6 +25,8 @@ pub struct Withdraw<'info> {
 
     #[account(
         mut,
+        has_one = mint_x @ AmmError::InvalidToken,
+        has_one = mint_y @ AmmError::InvalidToken,
         seeds =[b"config", config.seed.to_le_bytes().as_ref()],
         bump = config.config_bump,
     )]
@@ -74,6 +81,13 @@ pub struct Withdraw<'info> {
     )]
     pub user_y: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        mut,
+        seeds = [b"oracle", config.key().as_ref()],
+        bump = oracle.bump,
+    )]
+    pub oracle: Option<Account<'info, Oracle>>,
+
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -86,32 +100,37 @@ impl<'info> Withdraw<'info> {
         min_x: u64,  // Minimum amount of token X that the user wants to receive
         min_y: u64,  // Minimum amount of token Y that the user wants to receive
     ) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        // Concentrated liquidity goes through positions instead of the LP mint
+        require!(
+            self.config.curve != CurveType::Concentrated,
+            AmmError::UnsupportedCurve
+        );
         require!(amount != 0, AmmError::ZeroBalance);
         require!(amount <= self.user_lp.amount, AmmError::InsufficientBalance);
 
         // Calculate the tokens x and y required by the amount
         // formula: token_x = lp_tokens/ total_supply * total_token_x_vault
-        let vault_x_amount = self.vault_x.amount;
-        let vault_y_amount = self.vault_y.amount;
+        let (vault_x_amount, vault_y_amount) = self
+            .config
+            .reserves(self.vault_x.amount, self.vault_y.amount)?;
+
+        self.config.update_price_cumulative(
+            vault_x_amount,
+            vault_y_amount,
+            Clock::get()?.unix_timestamp,
+        );
+        record_observation(&self.config, self.oracle.as_deref_mut())?;
         let lp_supply = self.mint_lp.supply;
 
-        // required token_x to mint
-        let token_x = amount
-            .checked_mul(vault_x_amount)
-            .ok_or(AmmError::Overflow)?
-            .checked_div(lp_supply)
-            .ok_or(AmmError::Underflow)?;
-
-        // required token_y to mint
-        let token_y = amount
-            .checked_mul(vault_y_amount)
-            .ok_or(AmmError::Overflow)?
-            .checked_div(lp_supply)
-            .ok_or(AmmError::Underflow)?;
-
-        // keep account for slippage error
+        // Rounded down, so burning LP never takes more than its share of the pool
+        let (token_x, token_y) = quote_withdraw(vault_x_amount, vault_y_amount, lp_supply, amount)
+            .map_err(AmmError::from)?;
+
+        // keep account for slippage error, on what arrives after Token-2022 transfer fees
         require!(
-            token_x >= min_x && token_y >= min_y,
+            amount_after_fee(&self.mint_x, token_x)? >= min_x
+                && amount_after_fee(&self.mint_y, token_y)? >= min_y,
             AmmError::SlippageExceeded
         );
 
@@ -119,6 +138,19 @@ impl<'info> Withdraw<'info> {
         self.withdraw_tokens(false, token_y)?;
 
         self.burn_lp_tokens(amount)?;
+
+        let (reserve_x, reserve_y) = sync(&self.config, &mut self.vault_x, &mut self.vault_y)?;
+        self.mint_lp.reload()?;
+        emit!(LiquidityRemoved {
+            config: self.config.key(),
+            user: self.withdrawer.key(),
+            amount_x: token_x,
+            amount_y: token_y,
+            lp_amount: amount,
+            reserve_x,
+            reserve_y,
+            lp_supply: self.mint_lp.supply,
+        });
         Ok(())
     }
     pub fn withdraw_tokens(&self, is_x: bool, amount: u64) -> Result<()> {
@@ -139,9 +171,9 @@ impl<'info> Withdraw<'info> {
         };
 
         let transfer_account = TransferChecked {
-            from: from,
-            mint: mint,
-            to: to,
+            from,
+            mint,
+            to,
             authority: self.config.to_account_info(),
         };
 
@@ -172,8 +204,6 @@ impl<'info> Withdraw<'info> {
             CpiContext::new(self.token_program.to_account_info(), burn_accounts),
             amount,
             self.mint_lp.decimals,
-        );
-
-        Ok(())
+        )
     }
 }
@@ -0,0 +1,372 @@
+use amm_quote::quote_withdraw;
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        burn_checked, mint_to, transfer_checked, BurnChecked, Mint, MintTo, TokenAccount,
+        TokenInterface, TransferChecked,
+    },
+};
+
+use crate::{
+    errors::AmmError,
+    events::{sync, LiquidityAdded, LiquidityRemoved},
+    state::{record_observation, Config, CurveType, Oracle},
+    utils::amount_after_fee,
+};
+
+#[derive(Accounts)]
+pub struct Zap<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub mint_x: InterfaceAccount<'info, Mint>,
+    pub mint_y: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = mint_x,
+        has_one = mint_y,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"lp", config.key().as_ref()],
+        bump = config.lp_bump,
+    )]
+    pub mint_lp: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_x,
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_x: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_y,
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_y: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_x,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_x: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_y,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_y: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_lp,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_lp: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"oracle", config.key().as_ref()],
+        bump = oracle.bump,
+    )]
+    pub oracle: Option<Account<'info, Oracle>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Zap<'info> {
+    pub fn deposit_single(&mut self, is_x: bool, amount_in: u64, min_lp: u64) -> Result<()> {
+        let (reserve_in, reserve_out, now) = self.prepare(is_x)?;
+        require!(amount_in != 0, AmmError::InvalidAmount);
+
+        // Only what reaches the vault after Token-2022 transfer fees is zapped
+        let mint_in = match is_x {
+            true => &self.mint_x,
+            false => &self.mint_y,
+        };
+        let net_in = amount_after_fee(mint_in, amount_in)?;
+
+        // Swap part of the input inside the pool, the output never leaves the vault
+        // Sized at the base fee first, then again if the swap is charged a dynamic fee
+        let base_fee = self.config.base_fee();
+        let swap_in =
+            self.config
+                .zap_swap_amount(reserve_in, reserve_out, net_in, base_fee, now)?;
+        let fee = self
+            .config
+            .fee_for(is_x, reserve_in, reserve_out, swap_in, now)?;
+        let swap_in = match fee == base_fee {
+            true => swap_in,
+            false => self
+                .config
+                .zap_swap_amount(reserve_in, reserve_out, net_in, fee, now)?,
+        };
+        self.config
+            .record_fee_move(is_x, reserve_in, reserve_out, now);
+        let (swap_out, fee_amount) =
+            self.config
+                .swap_out(reserve_in, reserve_out, swap_in, fee, now)?;
+        let protocol_fee_amount = self.accrue_protocol_fee(is_x, fee_amount)?;
+
+        let reserve_in = (reserve_in as u128 + swap_in as u128)
+            .checked_sub(protocol_fee_amount as u128)
+            .ok_or(AmmError::Underflow)?;
+        let reserve_out = reserve_out
+            .checked_sub(swap_out)
+            .ok_or(AmmError::InsufficientBalance)? as u128;
+
+        // LP for the leftover input and the swapped output, any imbalance stays in the pool
+        let supply = self.mint_lp.supply as u128;
+        let lp_amount = ((net_in - swap_in) as u128 * supply / reserve_in)
+            .min(swap_out as u128 * supply / reserve_out);
+        let lp_amount = u64::try_from(lp_amount).map_err(|_| AmmError::Overflow)?;
+
+        require!(lp_amount != 0, AmmError::InvalidAmount);
+        require!(lp_amount >= min_lp, AmmError::SlippageExceeded);
+
+        self.deposit_tokens(is_x, amount_in)?;
+        self.mint_lp_tokens(lp_amount)?;
+
+        let (amount_x, amount_y) = match is_x {
+            true => (amount_in, 0),
+            false => (0, amount_in),
+        };
+        let (reserve_x, reserve_y, lp_supply) = self.reserves_after()?;
+        emit!(LiquidityAdded {
+            config: self.config.key(),
+            user: self.user.key(),
+            amount_x,
+            amount_y,
+            lp_amount,
+            reserve_x,
+            reserve_y,
+            lp_supply,
+        });
+        Ok(())
+    }
+
+    pub fn withdraw_single(&mut self, lp_amount: u64, is_x: bool, min_out: u64) -> Result<()> {
+        // Reserves seen from the side being swapped into the pool
+        let (reserve_in, reserve_out, now) = self.prepare(!is_x)?;
+        require!(lp_amount != 0, AmmError::InvalidAmount);
+        require!(
+            lp_amount <= self.user_lp.amount,
+            AmmError::InsufficientBalance
+        );
+
+        // Proportional share of both sides
+        let (share_in, share_out) =
+            quote_withdraw(reserve_in, reserve_out, self.mint_lp.supply, lp_amount)
+                .map_err(AmmError::from)?;
+
+        // Sell the unwanted side back to what is left of the pool
+        let (left_in, left_out) = (reserve_in - share_in, reserve_out - share_out);
+        let fee = self
+            .config
+            .fee_for(!is_x, left_in, left_out, share_in, now)?;
+        self.config.record_fee_move(!is_x, left_in, left_out, now);
+        let (swap_out, fee_amount) = self
+            .config
+            .swap_out(left_in, left_out, share_in, fee, now)?;
+        self.accrue_protocol_fee(!is_x, fee_amount)?;
+
+        let amount_out = share_out.checked_add(swap_out).ok_or(AmmError::Overflow)?;
+        require!(amount_out < reserve_out, AmmError::InsufficientBalance);
+        let mint_out = match is_x {
+            true => &self.mint_x,
+            false => &self.mint_y,
+        };
+        require!(
+            amount_after_fee(mint_out, amount_out)? >= min_out,
+            AmmError::SlippageExceeded
+        );
+
+        self.withdraw_tokens(is_x, amount_out)?;
+        self.burn_lp_tokens(lp_amount)?;
+
+        let (amount_x, amount_y) = match is_x {
+            true => (amount_out, 0),
+            false => (0, amount_out),
+        };
+        let (reserve_x, reserve_y, lp_supply) = self.reserves_after()?;
+        emit!(LiquidityRemoved {
+            config: self.config.key(),
+            user: self.user.key(),
+            amount_x,
+            amount_y,
+            lp_amount,
+            reserve_x,
+            reserve_y,
+            lp_supply,
+        });
+        Ok(())
+    }
+
+    // Checks the pool and advances the price accumulators, returns the reserves for `is_x` in
+    fn prepare(&mut self, is_x: bool) -> Result<(u64, u64, i64)> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(
+            self.config.curve != CurveType::Concentrated,
+            AmmError::UnsupportedCurve
+        );
+
+        let (reserve_x, reserve_y) = self
+            .config
+            .reserves(self.vault_x.amount, self.vault_y.amount)?;
+        require!(
+            self.mint_lp.supply != 0 && reserve_x != 0 && reserve_y != 0,
+            AmmError::NoLiquidityInPool
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        self.config
+            .update_price_cumulative(reserve_x, reserve_y, now);
+        record_observation(&self.config, self.oracle.as_deref_mut())?;
+
+        Ok(match is_x {
+            true => (reserve_x, reserve_y, now),
+            false => (reserve_y, reserve_x, now),
+        })
+    }
+
+    // Reserves and LP supply once the transfers and the mint or burn have landed
+    fn reserves_after(&mut self) -> Result<(u64, u64, u64)> {
+        let (reserve_x, reserve_y) = sync(&self.config, &mut self.vault_x, &mut self.vault_y)?;
+        self.mint_lp.reload()?;
+
+        Ok((reserve_x, reserve_y, self.mint_lp.supply))
+    }
+
+    fn accrue_protocol_fee(&mut self, is_x: bool, fee_amount: u64) -> Result<u64> {
+        let before = match is_x {
+            true => self.config.protocol_fees_x,
+            false => self.config.protocol_fees_y,
+        };
+        self.config.accrue_protocol_fee(is_x, fee_amount)?;
+
+        Ok(match is_x {
+            true => self.config.protocol_fees_x - before,
+            false => self.config.protocol_fees_y - before,
+        })
+    }
+
+    fn deposit_tokens(&self, is_x: bool, amount: u64) -> Result<()> {
+        let (from, to, mint, decimals) = match is_x {
+            true => (
+                &self.user_x,
+                &self.vault_x,
+                &self.mint_x,
+                self.mint_x.decimals,
+            ),
+            false => (
+                &self.user_y,
+                &self.vault_y,
+                &self.mint_y,
+                self.mint_y.decimals,
+            ),
+        };
+
+        let transfer_account = TransferChecked {
+            from: from.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+        transfer_checked(
+            CpiContext::new(self.token_program.to_account_info(), transfer_account),
+            amount,
+            decimals,
+        )
+    }
+
+    fn withdraw_tokens(&self, is_x: bool, amount: u64) -> Result<()> {
+        let (from, to, mint, decimals) = match is_x {
+            true => (
+                &self.vault_x,
+                &self.user_x,
+                &self.mint_x,
+                self.mint_x.decimals,
+            ),
+            false => (
+                &self.vault_y,
+                &self.user_y,
+                &self.mint_y,
+                self.mint_y.decimals,
+            ),
+        };
+
+        let transfer_account = TransferChecked {
+            from: from.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: self.config.to_account_info(),
+        };
+        let config_seeds = self.config.seed.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] =
+            &[&[b"config", config_seeds.as_ref(), &[self.config.config_bump]]];
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                transfer_account,
+                signer_seeds,
+            ),
+            amount,
+            decimals,
+        )
+    }
+
+    fn mint_lp_tokens(&self, amount: u64) -> Result<()> {
+        let mint_accounts = MintTo {
+            mint: self.mint_lp.to_account_info(),
+            to: self.user_lp.to_account_info(),
+            authority: self.config.to_account_info(),
+        };
+        let config_seeds = self.config.seed.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] =
+            &[&[b"config", config_seeds.as_ref(), &[self.config.config_bump]]];
+
+        mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                mint_accounts,
+                signer_seeds,
+            ),
+            amount,
+        )
+    }
+
+    fn burn_lp_tokens(&self, amount: u64) -> Result<()> {
+        let burn_accounts = BurnChecked {
+            mint: self.mint_lp.to_account_info(),
+            from: self.user_lp.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+
+        burn_checked(
+            CpiContext::new(self.token_program.to_account_info(), burn_accounts),
+            amount,
+            self.mint_lp.decimals,
+        )
+    }
+}
@@ -1,10 +1,13 @@
 use anchor_lang::prelude::*;
 
 mod errors;
+mod events;
 mod instructions;
 mod state;
+mod utils;
 
 use instructions::*;
+pub use state::CurveType;
 declare_id!("8DZ2UpRzstobVURn18Edw5tMtDaEPjjGrUiv3qdLCUBw");
 
 #[program]
@@ -17,8 +20,75 @@ pub mod anchor_amm {
         seed: u64,
         fee: u16,
         authority: Option<Pubkey>,
+        curve: CurveType,
+        amp: u64,
     ) -> Result<()> {
-        ctx.accounts.init(seed, fee, authority, ctx.bumps)
+        ctx.accounts
+            .init(seed, fee, authority, curve, amp, ctx.bumps)
+    }
+
+    pub fn initialize_factory(ctx: Context<InitializeFactory>, fee_tiers: Vec<u16>) -> Result<()> {
+        ctx.accounts.initialize_factory(fee_tiers, ctx.bumps)
+    }
+
+    pub fn add_fee_tier(ctx: Context<UpdateFactory>, fee_tier: u16) -> Result<()> {
+        ctx.accounts.add_fee_tier(fee_tier)
+    }
+
+    pub fn register_pool(ctx: Context<RegisterPool>, fee_tier: u16) -> Result<()> {
+        ctx.accounts.register_pool(fee_tier, ctx.bumps)
+    }
+
+    pub fn initialize_oracle(ctx: Context<InitializeOracle>) -> Result<()> {
+        ctx.accounts.initialize_oracle(ctx.bumps)
+    }
+
+    pub fn get_twap(ctx: Context<GetTwap>, window: i64) -> Result<Twap> {
+        ctx.accounts.get_twap(window)
+    }
+
+    pub fn initialize_concentrated(
+        ctx: Context<InitializeConcentrated>,
+        tick_spacing: u16,
+        sqrt_price: u128,
+    ) -> Result<()> {
+        ctx.accounts
+            .initialize_concentrated(tick_spacing, sqrt_price, ctx.bumps)
+    }
+
+    pub fn open_position(
+        ctx: Context<OpenPosition>,
+        tick_lower: i32,
+        tick_upper: i32,
+    ) -> Result<()> {
+        ctx.accounts
+            .open_position(tick_lower, tick_upper, ctx.bumps)
+    }
+
+    pub fn increase_liquidity(
+        ctx: Context<ModifyPosition>,
+        liquidity: u128,
+        max_x: u64,
+        max_y: u64,
+    ) -> Result<()> {
+        ctx.accounts.increase_liquidity(liquidity, max_x, max_y)
+    }
+
+    pub fn decrease_liquidity(
+        ctx: Context<ModifyPosition>,
+        liquidity: u128,
+        min_x: u64,
+        min_y: u64,
+    ) -> Result<()> {
+        ctx.accounts.decrease_liquidity(liquidity, min_x, min_y)
+    }
+
+    pub fn collect_fees(ctx: Context<ModifyPosition>) -> Result<()> {
+        ctx.accounts.collect_fees()
+    }
+
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        ctx.accounts.close_position()
     }
 
     pub fn deposit(ctx: Context<Deposit>, amount: u64, max_x: u64, max_y: u64) -> Result<()> {
@@ -29,7 +99,98 @@ pub mod anchor_amm {
         ctx.accounts.withdraw(amount, max_x, max_y)
     }
 
+    pub fn deposit_single(
+        ctx: Context<Zap>,
+        is_x: bool,
+        amount_in: u64,
+        min_lp: u64,
+    ) -> Result<()> {
+        ctx.accounts.deposit_single(is_x, amount_in, min_lp)
+    }
+
+    pub fn withdraw_single(
+        ctx: Context<Zap>,
+        lp_amount: u64,
+        is_x: bool,
+        min_out: u64,
+    ) -> Result<()> {
+        ctx.accounts.withdraw_single(lp_amount, is_x, min_out)
+    }
+
+    pub fn flash_loan(ctx: Context<FlashLoan>, amount_x: u64, amount_y: u64) -> Result<()> {
+        ctx.accounts.flash_loan(amount_x, amount_y)
+    }
+
+    pub fn flash_repay(ctx: Context<FlashLoan>) -> Result<()> {
+        ctx.accounts.flash_repay()
+    }
+
     pub fn swap(ctx: Context<Swap>, is_x: bool, amount_in: u64, min_amount_out: u64) -> Result<()> {
         ctx.accounts.swap(is_x, amount_in, min_amount_out)
     }
+
+    pub fn swap_exact_out(
+        ctx: Context<Swap>,
+        is_x: bool,
+        amount_out: u64,
+        max_amount_in: u64,
+    ) -> Result<()> {
+        ctx.accounts.swap_exact_out(is_x, amount_out, max_amount_in)
+    }
+
+    pub fn route_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RouteSwap<'info>>,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .route_swap(ctx.remaining_accounts, amount_in, min_amount_out)
+    }
+
+    pub fn lock(ctx: Context<Update>) -> Result<()> {
+        ctx.accounts.lock()
+    }
+
+    pub fn unlock(ctx: Context<Update>) -> Result<()> {
+        ctx.accounts.unlock()
+    }
+
+    pub fn transfer_authority(ctx: Context<Update>, new_authority: Pubkey) -> Result<()> {
+        ctx.accounts.transfer_authority(new_authority)
+    }
+
+    pub fn set_protocol_fee(ctx: Context<Update>, protocol_fee: u16) -> Result<()> {
+        ctx.accounts.set_protocol_fee(protocol_fee)
+    }
+
+    pub fn enable_dynamic_fee(
+        ctx: Context<Update>,
+        base_fee: u16,
+        max_fee: u16,
+        sensitivity: u16,
+        window: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .enable_dynamic_fee(base_fee, max_fee, sensitivity, window)
+    }
+
+    pub fn disable_dynamic_fee(ctx: Context<Update>) -> Result<()> {
+        ctx.accounts.disable_dynamic_fee()
+    }
+
+    pub fn ramp_amplification(ctx: Context<Update>, target_amp: u64, ramp_stop: i64) -> Result<()> {
+        ctx.accounts.ramp_amplification(target_amp, ramp_stop)
+    }
+
+    pub fn stop_ramp_amplification(ctx: Context<Update>) -> Result<()> {
+        ctx.accounts.stop_ramp_amplification()
+    }
+
+    pub fn collect_protocol_fees(ctx: Context<CollectProtocolFees>) -> Result<()> {
+        ctx.accounts.collect_protocol_fees()
+    }
+
+    pub fn renounce_authority(ctx: Context<Update>) -> Result<()> {
+        ctx.accounts.renounce_authority()
+    }
 }
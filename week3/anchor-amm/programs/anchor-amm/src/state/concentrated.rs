@@ -0,0 +1,225 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AmmError;
+
+#[allow(clippy::all)]
+mod u256 {
+    uint::construct_uint! {
+        pub struct U256(4);
+    }
+}
+pub use u256::U256;
+
+// Prices are sqrt(1.0001^tick) in Q64.64, bounded so the square still fits in the u128 range
+pub const MIN_TICK: i32 = -443636;
+pub const MAX_TICK: i32 = 443636;
+pub const MAX_TICK_SPACING: u16 = 16384;
+
+// sqrt(1.0001)^(2^i) in Q64.64
+const SQRT_POWERS: [u128; 19] = [
+    18447666387855959850,
+    18448588748116922571,
+    18450433606991734263,
+    18454123878217468680,
+    18461506635090006701,
+    18476281010653910144,
+    18505865242158250041,
+    18565175891880433522,
+    18684368066214940582,
+    18925053041275764671,
+    19415764168677886926,
+    20435687552633177494,
+    22639080592224303007,
+    27784196929998399742,
+    41848122137994986128,
+    94936283578220370716,
+    488590176327622479860,
+    12941056668319229769860,
+    9078618265828848800676189,
+];
+
+pub fn sqrt_price_at_tick(tick: i32) -> Result<u128> {
+    require!((MIN_TICK..=MAX_TICK).contains(&tick), AmmError::InvalidTick);
+
+    let mut ratio = U256::one() << 64;
+    let abs_tick = tick.unsigned_abs();
+    for (i, power) in SQRT_POWERS.iter().enumerate() {
+        if abs_tick & (1 << i) != 0 {
+            ratio = (ratio * U256::from(*power)) >> 64;
+        }
+    }
+
+    // Negative ticks are the reciprocal
+    if tick < 0 {
+        ratio = (U256::one() << 128) / ratio;
+    }
+    Ok(ratio.as_u128())
+}
+
+// Largest tick whose sqrt price is at or below `sqrt_price`
+pub fn tick_at_sqrt_price(sqrt_price: u128) -> Result<i32> {
+    let (mut low, mut high) = (MIN_TICK, MAX_TICK);
+    require!(
+        sqrt_price >= sqrt_price_at_tick(low)? && sqrt_price <= sqrt_price_at_tick(high)?,
+        AmmError::InvalidPrice
+    );
+
+    while low < high {
+        let mid = low + (high - low + 1) / 2;
+        match sqrt_price_at_tick(mid)? <= sqrt_price {
+            true => low = mid,
+            false => high = mid - 1,
+        }
+    }
+    Ok(low)
+}
+
+// X owed for `liquidity` between two sqrt prices: L * (b - a) / (a * b)
+pub fn amount_x_delta(a: u128, b: u128, liquidity: u128, round_up: bool) -> Result<u128> {
+    let (a, b) = (a.min(b), a.max(b));
+    require!(a != 0, AmmError::InvalidPrice);
+
+    let numerator = (U256::from(liquidity) << 64)
+        .checked_mul(U256::from(b - a))
+        .ok_or(AmmError::Overflow)?;
+    let amount = match round_up {
+        true => div_ceil(div_ceil(numerator, U256::from(b)), U256::from(a)),
+        false => numerator / U256::from(b) / U256::from(a),
+    };
+    to_u128(amount)
+}
+
+// Y owed for `liquidity` between two sqrt prices: L * (b - a)
+pub fn amount_y_delta(a: u128, b: u128, liquidity: u128, round_up: bool) -> Result<u128> {
+    let (a, b) = (a.min(b), a.max(b));
+
+    let product = U256::from(liquidity) * U256::from(b - a);
+    let amount = match round_up {
+        true => div_ceil(product, U256::one() << 64),
+        false => product >> 64,
+    };
+    to_u128(amount)
+}
+
+// Sqrt price after adding `amount` of the input token, rounded against the swapper
+pub fn next_sqrt_price(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount: u128,
+    is_x: bool,
+) -> Result<u128> {
+    require!(liquidity != 0, AmmError::NoLiquidityInPool);
+
+    match is_x {
+        // X in pushes the price down: L * P / (L + amount * P), rounded up
+        true => {
+            let numerator = U256::from(liquidity) << 64;
+            let price = U256::from(sqrt_price);
+            let exact = numerator.checked_mul(price).and_then(|product| {
+                let denominator = U256::from(amount)
+                    .checked_mul(price)?
+                    .checked_add(numerator)?;
+                Some(div_ceil(product, denominator))
+            });
+            match exact {
+                Some(next) => to_u128(next),
+                // L / (L / P + amount) rounds L / P down, so the price still moves no further
+                None => to_u128(div_ceil(numerator, numerator / price + U256::from(amount))),
+            }
+        }
+        // Y in pushes the price up: P + amount / L
+        false => {
+            let delta = to_u128((U256::from(amount) << 64) / U256::from(liquidity))?;
+            sqrt_price
+                .checked_add(delta)
+                .ok_or(AmmError::Overflow.into())
+        }
+    }
+}
+
+pub struct SwapStep {
+    pub sqrt_price: u128,
+    pub amount_in: u128,
+    pub amount_out: u128,
+    pub fee_amount: u128,
+}
+
+// One exact-input step towards `sqrt_target` with constant liquidity
+pub fn swap_step(
+    sqrt_price: u128,
+    sqrt_target: u128,
+    liquidity: u128,
+    remaining: u128,
+    fee: u16,
+) -> Result<SwapStep> {
+    let is_x = sqrt_target <= sqrt_price;
+    let remaining_less_fee = remaining * (10000 - fee as u128) / 10000;
+
+    let amount_to_target = match is_x {
+        true => amount_x_delta(sqrt_target, sqrt_price, liquidity, true)?,
+        false => amount_y_delta(sqrt_price, sqrt_target, liquidity, true)?,
+    };
+
+    let (next, amount_in) = match remaining_less_fee >= amount_to_target {
+        true => (sqrt_target, amount_to_target),
+        // Nothing is left once the fee is taken, the price stays put
+        false if remaining_less_fee == 0 => (sqrt_price, 0),
+        false => {
+            let next = next_sqrt_price(sqrt_price, liquidity, remaining_less_fee, is_x)?;
+            let amount_in = match is_x {
+                true => amount_x_delta(next, sqrt_price, liquidity, true)?,
+                false => amount_y_delta(sqrt_price, next, liquidity, true)?,
+            };
+            (next, amount_in)
+        }
+    };
+
+    let amount_out = match is_x {
+        true => amount_y_delta(next, sqrt_price, liquidity, false)?,
+        false => amount_x_delta(sqrt_price, next, liquidity, false)?,
+    };
+
+    // A partial step spends everything left, the rest of it is fee
+    let fee_amount = match next == sqrt_target {
+        true => (amount_in * fee as u128).div_ceil(10000 - fee as u128),
+        false => remaining
+            .checked_sub(amount_in)
+            .ok_or(AmmError::Underflow)?,
+    };
+
+    Ok(SwapStep {
+        sqrt_price: next,
+        amount_in,
+        amount_out,
+        fee_amount,
+    })
+}
+
+// `value` Q64.64 fee growth spread over `liquidity`
+pub fn fee_growth(value: u128, liquidity: u128) -> Result<u128> {
+    to_u128((U256::from(value) << 64) / U256::from(liquidity))
+}
+
+// Tokens earned by `liquidity` over a Q64.64 fee growth delta
+pub fn fees_earned(growth_delta: u128, liquidity: u128) -> Result<u64> {
+    let fees = U256::from(growth_delta)
+        .checked_mul(U256::from(liquidity))
+        .ok_or(AmmError::Overflow)?
+        >> 64;
+    to_u128(fees)?
+        .try_into()
+        .map_err(|_| AmmError::Overflow.into())
+}
+
+fn div_ceil(numerator: U256, denominator: U256) -> U256 {
+    let (quotient, remainder) = numerator.div_mod(denominator);
+    match remainder.is_zero() {
+        true => quotient,
+        false => quotient + 1,
+    }
+}
+
+fn to_u128(value: U256) -> Result<u128> {
+    require!(value <= U256::from(u128::MAX), AmmError::Overflow);
+    Ok(value.as_u128())
+}
@@ -1,5 +1,18 @@
 use anchor_lang::prelude::*;
 
+use amm_quote::{quote_swap, quote_swap_exact_out, Curve};
+
+use crate::{
+    errors::AmmError,
+    state::{
+        amount_x_delta, amount_y_delta, fee_growth, sqrt_price_at_tick, swap_step,
+        tick_at_sqrt_price, CurveType, DynamicFee, TickList, MAX_TICK, MIN_TICK, U256,
+    },
+};
+
+// Each step is a full StableSwap quote, the unmatched remainder just stays in the pool
+pub const ZAP_SEARCH_STEPS: u32 = 20;
+
 #[account]
 #[derive(InitSpace)]
 pub struct Config {
@@ -8,7 +21,466 @@ pub struct Config {
     pub mint_x: Pubkey,            // Token X
     pub mint_y: Pubkey,            // Token Y
     pub fee: u16,                  // Swap fee in basis points
+    pub dynamic_fee: DynamicFee,   // Volatility based fee, charged instead of fee when enabled
+    pub protocol_fee: u16,         // Share of the swap fee kept for the protocol, in basis points
+    pub protocol_fees_x: u64,      // Protocol fees in vault_x waiting to be collected
+    pub protocol_fees_y: u64,      // Protocol fees in vault_y waiting to be collected
+    pub flash_debt_x: u64,         // Flash loan principal plus fee owed to vault_x
+    pub flash_debt_y: u64,         // Flash loan principal plus fee owed to vault_y
     pub locked: bool,              // If the pool is locked
+    pub curve: CurveType,          // Invariant used to price swaps
+    pub amp_initial: u64,          // StableSwap amplification at the start of the ramp
+    pub amp_target: u64,           // StableSwap amplification at the end of the ramp
+    pub amp_ramp_start: i64,       // Timestamp the amplification ramp started
+    pub amp_ramp_stop: i64,        // Timestamp the amplification reaches amp_target
+    pub tick_spacing: u16,         // Concentrated: position bounds are multiples of this
+    pub sqrt_price: u128,          // Concentrated: Q64.64 square root of the price of X in Y
+    pub tick_current: i32,         // Concentrated: tick the current price is in
+    pub liquidity: u128,           // Concentrated: liquidity of the positions in range
+    pub fee_growth_global_x: u128, // Concentrated: Q64.64 X fees per unit of liquidity, wraps
+    pub fee_growth_global_y: u128, // Concentrated: Q64.64 Y fees per unit of liquidity, wraps
+    pub price_x_cumulative: u128,  // Sum of Q64.64 price of X in Y times seconds, wraps on overflow
+    pub price_y_cumulative: u128,  // Sum of Q64.64 price of Y in X times seconds, wraps on overflow
+    pub last_update: i64,          // Timestamp of the last cumulative price update
+    pub has_oracle: bool,          // Set once an oracle exists, every update must record into it
     pub config_bump: u8,           // Bump seed for the config account
     pub lp_bump: u8,               // Bump seed for the LP token
 }
+
+impl Config {
+    // Vault balances that belong to LPs, uncollected protocol fees excluded
+    // An outstanding flash loan still counts, the pool prices as if it was already repaid
+    pub fn reserves(&self, vault_x: u64, vault_y: u64) -> Result<(u64, u64)> {
+        Ok((
+            vault_x
+                .checked_add(self.flash_debt_x)
+                .ok_or(AmmError::Overflow)?
+                .checked_sub(self.protocol_fees_x)
+                .ok_or(AmmError::Underflow)?,
+            vault_y
+                .checked_add(self.flash_debt_y)
+                .ok_or(AmmError::Overflow)?
+                .checked_sub(self.protocol_fees_y)
+                .ok_or(AmmError::Underflow)?,
+        ))
+    }
+
+    // Q64.64 spot prices of X in Y and of Y in X, zero while the pool is empty
+    pub fn spot_price(&self, reserve_x: u64, reserve_y: u64) -> (u128, u128) {
+        match self.curve {
+            // Concentrated pools price from the tick state, their vaults are not the curve
+            CurveType::Concentrated => {
+                let price = U256::from(self.sqrt_price) * U256::from(self.sqrt_price);
+                match price.is_zero() {
+                    true => (0, 0),
+                    false => (
+                        (price >> 64).min(U256::from(u128::MAX)).as_u128(),
+                        ((U256::one() << 192) / price)
+                            .min(U256::from(u128::MAX))
+                            .as_u128(),
+                    ),
+                }
+            }
+            _ if reserve_x == 0 || reserve_y == 0 => (0, 0),
+            _ => (
+                ((reserve_y as u128) << 64) / reserve_x as u128,
+                ((reserve_x as u128) << 64) / reserve_y as u128,
+            ),
+        }
+    }
+
+    // Cumulative prices extrapolated to `now` with the reserves held since the last update
+    pub fn price_cumulative_at(&self, reserve_x: u64, reserve_y: u64, now: i64) -> (u128, u128) {
+        let elapsed = now.saturating_sub(self.last_update);
+        let (price_x, price_y) = self.spot_price(reserve_x, reserve_y);
+        if elapsed <= 0 || price_x == 0 || price_y == 0 {
+            return (self.price_x_cumulative, self.price_y_cumulative);
+        }
+
+        (
+            self.price_x_cumulative
+                .wrapping_add(price_x.wrapping_mul(elapsed as u128)),
+            self.price_y_cumulative
+                .wrapping_add(price_y.wrapping_mul(elapsed as u128)),
+        )
+    }
+
+    // Must run before the reserves change so the elapsed time is priced at the old reserves
+    pub fn update_price_cumulative(&mut self, reserve_x: u64, reserve_y: u64, now: i64) {
+        (self.price_x_cumulative, self.price_y_cumulative) =
+            self.price_cumulative_at(reserve_x, reserve_y, now);
+        self.last_update = now;
+    }
+
+    // Fee of a calm market, what flash loans pay and what the pool registers under
+    pub fn base_fee(&self) -> u16 {
+        match self.dynamic_fee.enabled {
+            true => self.dynamic_fee.base_fee,
+            false => self.fee,
+        }
+    }
+
+    // Fee for an exact-input swap, dynamic fee pools price in the move the swap itself makes
+    // Pure, so a quote can be sized more than once, record_fee_move runs for the swap executed
+    pub fn fee_for(
+        &self,
+        is_x: bool,
+        reserve_in: u64,
+        reserve_out: u64,
+        amount_in: u64,
+        now: i64,
+    ) -> Result<u16> {
+        if !self.dynamic_fee.enabled {
+            return Ok(self.fee);
+        }
+
+        // At the base fee the most input reaches the curve, so the price moves the furthest
+        let (amount_out, _) = self.swap_out(
+            reserve_in,
+            reserve_out,
+            amount_in,
+            self.dynamic_fee.base_fee,
+            now,
+        )?;
+        self.dynamic_swap_fee(is_x, reserve_in, reserve_out, amount_in, amount_out, now)
+    }
+
+    // Fee for an exact-output swap, priced the same way
+    pub fn fee_for_out(
+        &self,
+        is_x: bool,
+        reserve_in: u64,
+        reserve_out: u64,
+        amount_out: u64,
+        now: i64,
+    ) -> Result<u16> {
+        if !self.dynamic_fee.enabled {
+            return Ok(self.fee);
+        }
+
+        let (amount_in, _) = self.swap_in(
+            reserve_in,
+            reserve_out,
+            amount_out,
+            self.dynamic_fee.base_fee,
+            now,
+        )?;
+        self.dynamic_swap_fee(is_x, reserve_in, reserve_out, amount_in, amount_out, now)
+    }
+
+    fn dynamic_swap_fee(
+        &self,
+        is_x: bool,
+        reserve_in: u64,
+        reserve_out: u64,
+        amount_in: u64,
+        amount_out: u64,
+        now: i64,
+    ) -> Result<u16> {
+        let after_in = reserve_in
+            .checked_add(amount_in)
+            .ok_or(AmmError::Overflow)?;
+        let after_out = reserve_out
+            .checked_sub(amount_out)
+            .ok_or(AmmError::InsufficientBalance)?;
+
+        let ((before, _), (after, _)) = match is_x {
+            true => (
+                self.spot_price(reserve_in, reserve_out),
+                self.spot_price(after_in, after_out),
+            ),
+            false => (
+                self.spot_price(reserve_out, reserve_in),
+                self.spot_price(after_out, after_in),
+            ),
+        };
+        Ok(self.dynamic_fee.fee(before, after, now))
+    }
+
+    // Rolls the dynamic fee window with the pre-swap reserves, once per executed swap
+    pub fn record_fee_move(&mut self, is_x: bool, reserve_in: u64, reserve_out: u64, now: i64) {
+        if !self.dynamic_fee.enabled {
+            return;
+        }
+
+        let (before, _) = match is_x {
+            true => self.spot_price(reserve_in, reserve_out),
+            false => self.spot_price(reserve_out, reserve_in),
+        };
+        self.dynamic_fee.record(before, now);
+    }
+
+    // StableSwap amplification, moving linearly from amp_initial to amp_target during a ramp
+    pub fn amplification(&self, now: i64) -> u64 {
+        if now >= self.amp_ramp_stop {
+            return self.amp_target;
+        }
+        if now <= self.amp_ramp_start {
+            return self.amp_initial;
+        }
+
+        let elapsed = (now - self.amp_ramp_start) as u128;
+        let duration = (self.amp_ramp_stop - self.amp_ramp_start) as u128;
+        let (initial, target) = (self.amp_initial as u128, self.amp_target as u128);
+
+        let amp = match target >= initial {
+            true => initial + (target - initial) * elapsed / duration,
+            false => initial - (initial - target) * elapsed / duration,
+        };
+        amp as u64
+    }
+
+    // Curve of the shared quote math, concentrated pools swap through swap_concentrated
+    pub fn quote_curve(&self, now: i64) -> Result<Curve> {
+        match self.curve {
+            CurveType::ConstantProduct => Ok(Curve::ConstantProduct),
+            CurveType::StableSwap => Ok(Curve::StableSwap {
+                amp: self.amplification(now),
+            }),
+            CurveType::Concentrated => err!(AmmError::UnsupportedCurve),
+        }
+    }
+
+    // Output and fee of an exact-input swap of `amount` against the given reserves
+    pub fn swap_out(
+        &self,
+        reserve_in: u64,
+        reserve_out: u64,
+        amount: u64,
+        fee: u16,
+        now: i64,
+    ) -> Result<(u64, u64)> {
+        let quote = quote_swap(self.quote_curve(now)?, reserve_in, reserve_out, amount, fee)
+            .map_err(AmmError::from)?;
+
+        Ok((quote.amount_out, quote.fee))
+    }
+
+    // Part of a single-sided `amount` to swap so the rest matches the post-swap pool ratio
+    pub fn zap_swap_amount(
+        &self,
+        reserve_in: u64,
+        reserve_out: u64,
+        amount: u64,
+        fee: u16,
+        now: i64,
+    ) -> Result<u64> {
+        match self.curve {
+            // Positive root of g*s^2 + R*(1 + g)*s - A*R = 0, with g the input share kept after the fee
+            CurveType::ConstantProduct => {
+                require!(fee < 10000, AmmError::InvalidFee);
+                let (reserve, amount) = (U256::from(reserve_in), U256::from(amount));
+                let g = U256::from(10000 - fee);
+
+                let b = reserve * (g + 10000);
+                let root = (b * b + reserve * amount * g * 40000).integer_sqrt();
+                Ok(((root - b) / (g * 2)).as_u64())
+            }
+            _ => self.zap_search(reserve_in, reserve_out, amount, fee, now),
+        }
+    }
+
+    // Bisection for curves without a closed form, stops within amount / 2^ZAP_SEARCH_STEPS
+    fn zap_search(
+        &self,
+        reserve_in: u64,
+        reserve_out: u64,
+        amount: u64,
+        fee: u16,
+        now: i64,
+    ) -> Result<u64> {
+        let (mut low, mut high) = (0, amount);
+        for _ in 0..ZAP_SEARCH_STEPS {
+            if low >= high {
+                break;
+            }
+            let mid = low + (high - low).div_ceil(2);
+            let (swap_out, _) = self.swap_out(reserve_in, reserve_out, mid, fee, now)?;
+
+            // Largest swap whose leftover input still covers the output's share of the pool
+            let leftover = (amount - mid) as u128 * reserve_out.saturating_sub(swap_out) as u128;
+            let needed = swap_out as u128 * (reserve_in as u128 + mid as u128);
+            match leftover >= needed {
+                true => low = mid,
+                false => high = mid - 1,
+            }
+        }
+
+        Ok(low)
+    }
+
+    // Input, fee included, needed to take exactly `amount_out` out, and the fee
+    pub fn swap_in(
+        &self,
+        reserve_in: u64,
+        reserve_out: u64,
+        amount_out: u64,
+        fee: u16,
+        now: i64,
+    ) -> Result<(u64, u64)> {
+        let quote = quote_swap_exact_out(
+            self.quote_curve(now)?,
+            reserve_in,
+            reserve_out,
+            amount_out,
+            fee,
+        )
+        .map_err(AmmError::from)?;
+
+        Ok((quote.amount_in, quote.fee))
+    }
+
+    // Exact-input swap across the initialized ticks, returns the input used, the output and the fee
+    pub fn swap_concentrated(
+        &mut self,
+        ticks: &mut TickList,
+        is_x: bool,
+        amount: u64,
+    ) -> Result<(u64, u64, u64)> {
+        require!(self.sqrt_price != 0, AmmError::InvalidPrice);
+
+        let mut remaining = amount as u128;
+        let mut amount_out: u128 = 0;
+        let mut fee_amount: u128 = 0;
+        let mut protocol_fee_amount: u128 = 0;
+
+        while remaining > 0 {
+            // X in moves the price down towards the next tick at or below the current one
+            let next_tick = ticks.next_tick(self.tick_current, is_x);
+            let target_tick = next_tick.unwrap_or(match is_x {
+                true => MIN_TICK,
+                false => MAX_TICK,
+            });
+            let sqrt_target = sqrt_price_at_tick(target_tick)?;
+
+            let step = swap_step(
+                self.sqrt_price,
+                sqrt_target,
+                self.liquidity,
+                remaining,
+                self.fee,
+            )?;
+            remaining -= step.amount_in + step.fee_amount;
+            amount_out = amount_out
+                .checked_add(step.amount_out)
+                .ok_or(AmmError::Overflow)?;
+            fee_amount += step.fee_amount;
+
+            // LPs in range share the fee, minus the protocol cut
+            if self.liquidity > 0 && step.fee_amount > 0 {
+                let protocol_part = step.fee_amount * self.protocol_fee as u128 / 10000;
+                protocol_fee_amount += protocol_part;
+                let growth = fee_growth(step.fee_amount - protocol_part, self.liquidity)?;
+                match is_x {
+                    true => {
+                        self.fee_growth_global_x = self.fee_growth_global_x.wrapping_add(growth)
+                    }
+                    false => {
+                        self.fee_growth_global_y = self.fee_growth_global_y.wrapping_add(growth)
+                    }
+                }
+            }
+
+            self.sqrt_price = step.sqrt_price;
+            if step.sqrt_price != sqrt_target {
+                self.tick_current = tick_at_sqrt_price(step.sqrt_price)?;
+                break;
+            }
+
+            // Reached the edge of the price range with input left over
+            let Some(index) = next_tick else {
+                self.tick_current = match is_x {
+                    true => target_tick - 1,
+                    false => target_tick,
+                };
+                break;
+            };
+
+            let liquidity_net =
+                ticks.cross(index, (self.fee_growth_global_x, self.fee_growth_global_y))?;
+            self.liquidity = match is_x {
+                true => self.liquidity.checked_add_signed(-liquidity_net),
+                false => self.liquidity.checked_add_signed(liquidity_net),
+            }
+            .ok_or(AmmError::Underflow)?;
+            self.tick_current = match is_x {
+                true => index - 1,
+                false => index,
+            };
+        }
+
+        let protocol_fees = match is_x {
+            true => &mut self.protocol_fees_x,
+            false => &mut self.protocol_fees_y,
+        };
+        let protocol_fee_amount =
+            u64::try_from(protocol_fee_amount).map_err(|_| AmmError::Overflow)?;
+        *protocol_fees = protocol_fees
+            .checked_add(protocol_fee_amount)
+            .ok_or(AmmError::Overflow)?;
+
+        let used = (amount as u128)
+            .checked_sub(remaining)
+            .ok_or(AmmError::Underflow)?;
+        Ok((
+            used.try_into().map_err(|_| AmmError::Overflow)?,
+            amount_out.try_into().map_err(|_| AmmError::Overflow)?,
+            fee_amount.try_into().map_err(|_| AmmError::Overflow)?,
+        ))
+    }
+
+    // Token amounts backing `liquidity` in a range at the current price
+    pub fn liquidity_amounts(
+        &self,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity: u128,
+        round_up: bool,
+    ) -> Result<(u64, u64)> {
+        let sqrt_lower = sqrt_price_at_tick(tick_lower)?;
+        let sqrt_upper = sqrt_price_at_tick(tick_upper)?;
+
+        // Below the range it is all X, above it all Y
+        let (x, y) = if self.tick_current < tick_lower {
+            (
+                amount_x_delta(sqrt_lower, sqrt_upper, liquidity, round_up)?,
+                0,
+            )
+        } else if self.tick_current < tick_upper {
+            (
+                amount_x_delta(self.sqrt_price, sqrt_upper, liquidity, round_up)?,
+                amount_y_delta(sqrt_lower, self.sqrt_price, liquidity, round_up)?,
+            )
+        } else {
+            (
+                0,
+                amount_y_delta(sqrt_lower, sqrt_upper, liquidity, round_up)?,
+            )
+        };
+
+        Ok((
+            x.try_into().map_err(|_| AmmError::Overflow)?,
+            y.try_into().map_err(|_| AmmError::Overflow)?,
+        ))
+    }
+
+    // Protocol share of a swap fee, the rest stays in the vault for LPs
+    pub fn accrue_protocol_fee(&mut self, is_x: bool, fee_amount: u64) -> Result<()> {
+        let protocol_fee_amount: u64 = ((fee_amount as u128)
+            .checked_mul(self.protocol_fee as u128)
+            .ok_or(AmmError::Overflow)?
+            / 10000)
+            .try_into()
+            .map_err(|_| AmmError::Overflow)?;
+
+        let protocol_fees = match is_x {
+            true => &mut self.protocol_fees_x,
+            false => &mut self.protocol_fees_y,
+        };
+        *protocol_fees = protocol_fees
+            .checked_add(protocol_fee_amount)
+            .ok_or(AmmError::Overflow)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_AMP: u64 = 1_000_000;
+pub const MAX_AMP_CHANGE: u64 = 10; // Largest factor the amplification can move by in one ramp
+pub const MIN_RAMP_DURATION: i64 = 86400;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum CurveType {
+    ConstantProduct, // x * y = k
+    StableSwap,      // Curve-style invariant for pegged pairs
+    Concentrated,    // Liquidity provided within tick ranges
+}
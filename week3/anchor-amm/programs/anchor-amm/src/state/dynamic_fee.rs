@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct DynamicFee {
+    pub enabled: bool,         // If swaps pay this instead of the pool's static fee
+    pub base_fee: u16,         // Fee in basis points while the price is calm
+    pub max_fee: u16,          // Cap on the fee in basis points
+    pub sensitivity: u16,      // Fee bps added per bps of price move, scaled by 10000
+    pub window: i64,           // Seconds a reference price is kept before it resets
+    pub reference_price: u128, // Q64.64 price of X in Y at the start of the window
+    pub window_start: i64,     // Timestamp the current window started
+}
+
+impl DynamicFee {
+    // A swap at `now` starts a new window at its own price
+    fn window_expired(&self, now: i64) -> bool {
+        self.reference_price == 0 || now.saturating_sub(self.window_start) >= self.window
+    }
+
+    // Fee for a swap moving the price from `before` to `after`, growing with whichever is further
+    // from the window's reference price
+    pub fn fee(&self, before: u128, after: u128, now: i64) -> u16 {
+        let reference = match self.window_expired(now) {
+            true => before,
+            false => self.reference_price,
+        };
+        if reference == 0 {
+            return self.base_fee;
+        }
+
+        let deviation = before.abs_diff(reference).max(after.abs_diff(reference));
+        let deviation_bps = deviation.saturating_mul(10000) / reference;
+        let variable_fee = deviation_bps.saturating_mul(self.sensitivity as u128) / 10000;
+
+        (self.base_fee as u128)
+            .saturating_add(variable_fee)
+            .min(self.max_fee as u128) as u16
+    }
+
+    // Starts a new window at `before` if the current one has run out, once per executed swap
+    pub fn record(&mut self, before: u128, now: i64) {
+        if self.window_expired(now) {
+            self.reference_price = before;
+            self.window_start = now;
+        }
+    }
+}
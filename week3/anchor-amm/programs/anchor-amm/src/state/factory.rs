@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+
+use crate::errors::AmmError;
+
+pub const MAX_FEE_TIERS: usize = 8;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Factory {
+    pub authority: Pubkey, // Can enable new fee tiers
+    #[max_len(MAX_FEE_TIERS)]
+    pub fee_tiers: Vec<u16>, // Swap fees in basis points pools can be registered under
+    pub pool_count: u64,   // Pools registered so far
+    pub bump: u8,
+}
+
+impl Factory {
+    pub fn add_fee_tier(&mut self, fee_tier: u16) -> Result<()> {
+        require!(fee_tier < 10000, AmmError::InvalidFee);
+        require!(
+            !self.fee_tiers.contains(&fee_tier),
+            AmmError::InvalidFeeTier
+        );
+        require!(
+            self.fee_tiers.len() < MAX_FEE_TIERS,
+            AmmError::TooManyFeeTiers
+        );
+        self.fee_tiers.push(fee_tier);
+
+        Ok(())
+    }
+}
+
+// One per (sorted mint pair, fee tier), points at the pool registered for it
+#[account]
+#[derive(InitSpace)]
+pub struct Pair {
+    pub mint_a: Pubkey, // Lower of the two mints
+    pub mint_b: Pubkey, // Higher of the two mints
+    pub fee_tier: u16,  // Swap fee in basis points the pool was registered with
+    pub config: Pubkey, // Registered pool
+    pub bump: u8,
+}
+
+impl Pair {
+    // Config seed derived from the pair, pools created with it can be found from the mints alone
+    pub fn canonical_seed(mint_a: &Pubkey, mint_b: &Pubkey, fee_tier: u16) -> u64 {
+        let (mint_a, mint_b) = match mint_a < mint_b {
+            true => (mint_a, mint_b),
+            false => (mint_b, mint_a),
+        };
+        let hash = hashv(&[
+            mint_a.as_ref(),
+            mint_b.as_ref(),
+            fee_tier.to_le_bytes().as_ref(),
+        ]);
+
+        u64::from_le_bytes(hash.to_bytes()[..8].try_into().unwrap())
+    }
+}
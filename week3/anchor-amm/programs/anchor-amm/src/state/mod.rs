@@ -1,3 +1,17 @@
+pub mod concentrated;
 pub mod config;
+pub mod curve;
+pub mod dynamic_fee;
+pub mod factory;
+pub mod oracle;
+pub mod position;
+pub mod tick;
 
+pub use concentrated::*;
 pub use config::*;
+pub use curve::*;
+pub use dynamic_fee::*;
+pub use factory::*;
+pub use oracle::*;
+pub use position::*;
+pub use tick::*;
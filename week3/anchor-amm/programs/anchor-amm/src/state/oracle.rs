@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::AmmError, state::Config};
+
+pub const MAX_OBSERVATIONS: usize = 64;
+// Keeps the buffer covering at least an hour, so a burst of swaps cannot flush it
+pub const OBSERVATION_INTERVAL: i64 = 60;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct Observation {
+    pub timestamp: i64,
+    pub price_x_cumulative: u128,
+    pub price_y_cumulative: u128,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Oracle {
+    pub config: Pubkey, // Pool the observations belong to
+    pub index: u16,     // Slot of the most recent observation
+    #[max_len(MAX_OBSERVATIONS)]
+    pub observations: Vec<Observation>, // Ring buffer of cumulative price snapshots
+    pub bump: u8,
+}
+
+// Pools without an oracle skip recording, the others must not be able to leave it out
+pub fn record_observation(config: &Config, oracle: Option<&mut Oracle>) -> Result<()> {
+    match oracle {
+        Some(oracle) => oracle.record(config),
+        None => require!(!config.has_oracle, AmmError::MissingOracle),
+    }
+
+    Ok(())
+}
+
+impl Oracle {
+    // Starts the buffer at the pool's current prices, every later update must record into it
+    pub fn initialize(&mut self, config_key: Pubkey, config: &mut Config, bump: u8) {
+        self.config = config_key;
+        self.index = 0;
+        self.observations = Vec::new();
+        self.bump = bump;
+        self.record(config);
+        config.has_oracle = true;
+    }
+
+    // Snapshots the pool's cumulative prices, at most once per interval
+    pub fn record(&mut self, config: &Config) {
+        let observation = Observation {
+            timestamp: config.last_update,
+            price_x_cumulative: config.price_x_cumulative,
+            price_y_cumulative: config.price_y_cumulative,
+        };
+
+        if let Some(last) = self.observations.get(self.index as usize) {
+            if observation.timestamp < last.timestamp + OBSERVATION_INTERVAL {
+                return;
+            }
+        }
+
+        if self.observations.len() < MAX_OBSERVATIONS {
+            self.observations.push(observation);
+            self.index = (self.observations.len() - 1) as u16;
+        } else {
+            self.index = ((self.index as usize + 1) % MAX_OBSERVATIONS) as u16;
+            self.observations[self.index as usize] = observation;
+        }
+    }
+
+    // Q64.64 average prices from the newest observation at least `window` seconds old until `now`
+    pub fn twap(
+        &self,
+        price_x_cumulative: u128,
+        price_y_cumulative: u128,
+        now: i64,
+        window: i64,
+    ) -> Result<(u128, u128)> {
+        require!(window > 0, AmmError::InvalidAmount);
+
+        let observation = self
+            .observations
+            .iter()
+            .filter(|observation| observation.timestamp <= now - window)
+            .max_by_key(|observation| observation.timestamp)
+            .ok_or(AmmError::OracleWindowTooLong)?;
+        let elapsed = (now - observation.timestamp) as u128;
+
+        Ok((
+            price_x_cumulative.wrapping_sub(observation.price_x_cumulative) / elapsed,
+            price_y_cumulative.wrapping_sub(observation.price_y_cumulative) / elapsed,
+        ))
+    }
+}
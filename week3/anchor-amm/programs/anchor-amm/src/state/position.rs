@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::AmmError, state::fees_earned};
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub owner: Pubkey,                  // LP that owns the position
+    pub config: Pubkey,                 // Concentrated pool the position is in
+    pub tick_lower: i32,                // Lower bound of the price range
+    pub tick_upper: i32,                // Upper bound of the price range
+    pub liquidity: u128,                // Liquidity provided within the range
+    pub fee_growth_inside_x_last: u128, // Q64.64 X fee growth inside the range at the last update
+    pub fee_growth_inside_y_last: u128, // Q64.64 Y fee growth inside the range at the last update
+    pub fees_owed_x: u64,               // X fees waiting to be collected
+    pub fees_owed_y: u64,               // Y fees waiting to be collected
+    pub bump: u8,
+}
+
+impl Position {
+    // Credits fees earned since the last update, then applies the liquidity change
+    pub fn update(&mut self, liquidity_delta: i128, fee_growth_inside: (u128, u128)) -> Result<()> {
+        let (inside_x, inside_y) = fee_growth_inside;
+        let earned_x = fees_earned(
+            inside_x.wrapping_sub(self.fee_growth_inside_x_last),
+            self.liquidity,
+        )?;
+        let earned_y = fees_earned(
+            inside_y.wrapping_sub(self.fee_growth_inside_y_last),
+            self.liquidity,
+        )?;
+
+        self.fees_owed_x = self
+            .fees_owed_x
+            .checked_add(earned_x)
+            .ok_or(AmmError::Overflow)?;
+        self.fees_owed_y = self
+            .fees_owed_y
+            .checked_add(earned_y)
+            .ok_or(AmmError::Overflow)?;
+        (self.fee_growth_inside_x_last, self.fee_growth_inside_y_last) = (inside_x, inside_y);
+
+        self.liquidity = self
+            .liquidity
+            .checked_add_signed(liquidity_delta)
+            .ok_or(AmmError::Underflow)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::AmmError;
+
+pub const MAX_TICKS: usize = 128;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct Tick {
+    pub index: i32,                 // Price boundary, sqrt(1.0001^index)
+    pub liquidity_net: i128,        // Liquidity added when the price crosses upwards
+    pub liquidity_gross: u128,      // Total liquidity of the positions using this tick
+    pub fee_growth_outside_x: u128, // Q64.64 X fees per liquidity on the other side
+    pub fee_growth_outside_y: u128, // Q64.64 Y fees per liquidity on the other side
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct TickList {
+    pub config: Pubkey, // Concentrated pool the ticks belong to
+    #[max_len(MAX_TICKS)]
+    pub ticks: Vec<Tick>, // Initialized ticks, sorted by index
+    pub bump: u8,
+}
+
+impl TickList {
+    // Next initialized tick moving down (at or below `tick_current`) or up (above it)
+    pub fn next_tick(&self, tick_current: i32, down: bool) -> Option<i32> {
+        match down {
+            true => self
+                .ticks
+                .iter()
+                .rev()
+                .find(|tick| tick.index <= tick_current)
+                .map(|tick| tick.index),
+            false => self
+                .ticks
+                .iter()
+                .find(|tick| tick.index > tick_current)
+                .map(|tick| tick.index),
+        }
+    }
+
+    // Adds `liquidity_delta` to a position boundary, initializing or clearing the tick as needed
+    pub fn update(
+        &mut self,
+        index: i32,
+        liquidity_delta: i128,
+        upper: bool,
+        tick_current: i32,
+        fee_growth_global: (u128, u128),
+    ) -> Result<()> {
+        let position = match self.ticks.binary_search_by_key(&index, |tick| tick.index) {
+            Ok(position) => position,
+            Err(position) => {
+                require!(self.ticks.len() < MAX_TICKS, AmmError::TooManyTicks);
+
+                // By convention all fees so far were earned below a tick at or under the price
+                let (outside_x, outside_y) = match index <= tick_current {
+                    true => fee_growth_global,
+                    false => (0, 0),
+                };
+                self.ticks.insert(
+                    position,
+                    Tick {
+                        index,
+                        liquidity_net: 0,
+                        liquidity_gross: 0,
+                        fee_growth_outside_x: outside_x,
+                        fee_growth_outside_y: outside_y,
+                    },
+                );
+                position
+            }
+        };
+
+        let tick = &mut self.ticks[position];
+        tick.liquidity_gross = tick
+            .liquidity_gross
+            .checked_add_signed(liquidity_delta)
+            .ok_or(AmmError::Overflow)?;
+        tick.liquidity_net = match upper {
+            true => tick.liquidity_net.checked_sub(liquidity_delta),
+            false => tick.liquidity_net.checked_add(liquidity_delta),
+        }
+        .ok_or(AmmError::Overflow)?;
+
+        if tick.liquidity_gross == 0 {
+            self.ticks.remove(position);
+        }
+
+        Ok(())
+    }
+
+    // Flips the outside fee growth of a crossed tick and returns its net liquidity
+    pub fn cross(&mut self, index: i32, fee_growth_global: (u128, u128)) -> Result<i128> {
+        let position = self
+            .ticks
+            .binary_search_by_key(&index, |tick| tick.index)
+            .map_err(|_| AmmError::InvalidTick)?;
+        let tick = &mut self.ticks[position];
+
+        tick.fee_growth_outside_x = fee_growth_global.0.wrapping_sub(tick.fee_growth_outside_x);
+        tick.fee_growth_outside_y = fee_growth_global.1.wrapping_sub(tick.fee_growth_outside_y);
+
+        Ok(tick.liquidity_net)
+    }
+
+    // Q64.64 fees per liquidity earned between two initialized ticks
+    pub fn fee_growth_inside(
+        &self,
+        lower: i32,
+        upper: i32,
+        tick_current: i32,
+        fee_growth_global: (u128, u128),
+    ) -> Result<(u128, u128)> {
+        let lower = self.get(lower)?;
+        let upper = self.get(upper)?;
+        let (global_x, global_y) = fee_growth_global;
+
+        let (below_x, below_y) = match tick_current >= lower.index {
+            true => (lower.fee_growth_outside_x, lower.fee_growth_outside_y),
+            false => (
+                global_x.wrapping_sub(lower.fee_growth_outside_x),
+                global_y.wrapping_sub(lower.fee_growth_outside_y),
+            ),
+        };
+        let (above_x, above_y) = match tick_current < upper.index {
+            true => (upper.fee_growth_outside_x, upper.fee_growth_outside_y),
+            false => (
+                global_x.wrapping_sub(upper.fee_growth_outside_x),
+                global_y.wrapping_sub(upper.fee_growth_outside_y),
+            ),
+        };
+
+        Ok((
+            global_x.wrapping_sub(below_x).wrapping_sub(above_x),
+            global_y.wrapping_sub(below_y).wrapping_sub(above_y),
+        ))
+    }
+
+    fn get(&self, index: i32) -> Result<&Tick> {
+        self.ticks
+            .binary_search_by_key(&index, |tick| tick.index)
+            .map(|position| &self.ticks[position])
+            .map_err(|_| AmmError::InvalidTick.into())
+    }
+}
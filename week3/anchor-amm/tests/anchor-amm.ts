@@ -2,15 +2,28 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { AnchorAmm } from "../target/types/anchor_amm";
 import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
   createAssociatedTokenAccount,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
   createMint,
+  ExtensionType,
   getAccount,
   getAssociatedTokenAddressSync,
+  getMintLen,
   mintTo,
+  TOKEN_2022_PROGRAM_ID,
   TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
-import { PublicKey } from "@solana/web3.js";
+import {
+  Keypair,
+  PublicKey,
+  sendAndConfirmTransaction,
+  SystemProgram,
+  Transaction,
+} from "@solana/web3.js";
 import { isSome } from "@metaplex-foundation/umi";
+import { createHash } from "crypto";
 
 describe("anchor-amm", () => {
   // Configure the client to use the local cluster.
@@ -36,6 +49,8 @@ describe("anchor-amm", () => {
   // vaults
   let vault_x: PublicKey;
   let vault_y: PublicKey;
+  // oracle
+  let oracle: PublicKey;
 
   before("Tokens and PDA setup", async () => {
     // derive the address for config
@@ -59,6 +74,11 @@ describe("anchor-amm", () => {
     );
     console.log("lp_mint created: ", lp_mint);
 
+    [oracle] = PublicKey.findProgramAddressSync(
+      [Buffer.from("oracle"), config_addr.toBuffer()],
+      program.programId
+    );
+
     // create and mint payers ATA for x, y mints
     payer_x_ata = await createAssociatedTokenAccount(
       connection,
@@ -91,19 +111,277 @@ describe("anchor-amm", () => {
 
   const program = anchor.workspace.anchorAmm as Program<AnchorAmm>;
 
+  it("Initialize rejects a fee of 100% or more", async () => {
+    try {
+      await program.methods
+        .initialize(
+          new anchor.BN(8888),
+          10000,
+          payer.publicKey,
+          { constantProduct: {} },
+          new anchor.BN(0)
+        )
+        .accounts({
+          initializer: payer.publicKey,
+          mintX: mint_x,
+          mintY: mint_y,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      throw new Error("A fee of 10000 bps should be rejected");
+    } catch (err) {
+      if (!err.toString().includes("InvalidFee")) throw err;
+    }
+  });
+
   it("Is initialized!", async () => {
     // Add your test here.
     const tx = await program.methods
-      .initialize(seeds, 300, payer.publicKey)
+      .initialize(
+        seeds,
+        300,
+        payer.publicKey,
+        { constantProduct: {} },
+        new anchor.BN(0)
+      )
       .accounts({
         initializer: payer.publicKey,
         mintX: mint_x,
         mintY: mint_y,
+        tokenProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
     console.log("Initialized config", tx);
   });
 
+  it("Initialize oracle", async () => {
+    const tx = await program.methods
+      .initializeOracle()
+      .accounts({
+        payer: payer.publicKey,
+        authority: payer.publicKey,
+        config: config_addr,
+      })
+      .rpc();
+    console.log("Initialized oracle", tx);
+  });
+
+  it("Only the pool authority can add an oracle later", async () => {
+    const poolAddress = (seed: anchor.BN) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("config"), seed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+    const oracleAddress = (pool: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("oracle"), pool.toBuffer()],
+        program.programId
+      )[0];
+    const initialize = (
+      seed: anchor.BN,
+      authority: PublicKey | null,
+      withOracle: boolean
+    ) =>
+      program.methods
+        .initialize(
+          seed,
+          30,
+          authority,
+          { constantProduct: {} },
+          new anchor.BN(0)
+        )
+        .accounts({
+          initializer: payer.publicKey,
+          mintX: mint_x,
+          mintY: mint_y,
+          oracle: withOracle ? oracleAddress(poolAddress(seed)) : null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    const addOracle = (pool: PublicKey, authority: Keypair) =>
+      program.methods
+        .initializeOracle()
+        .accounts({
+          payer: payer.publicKey,
+          authority: authority.publicKey,
+          config: pool,
+        })
+        .signers([authority])
+        .rpc();
+
+    // Anyone else adding one would break every client swapping without it
+    const owned = new anchor.BN(5555);
+    await initialize(owned, payer.publicKey, false);
+    try {
+      await addOracle(poolAddress(owned), Keypair.generate());
+      throw new Error("Only the pool authority should add an oracle");
+    } catch (err) {
+      if (!err.toString().includes("InvalidAuthority")) throw err;
+    }
+
+    const ownerless = new anchor.BN(6666);
+    await initialize(ownerless, null, false);
+    try {
+      await addOracle(poolAddress(ownerless), Keypair.generate());
+      throw new Error("A pool without an authority can't add an oracle later");
+    } catch (err) {
+      if (!err.toString().includes("NoAuthoritySet")) throw err;
+    }
+
+    // Without an authority the oracle comes with the pool
+    const withOracle = new anchor.BN(7777);
+    await initialize(withOracle, null, true);
+    const pool = await program.account.config.fetch(poolAddress(withOracle));
+    const poolOracle = await program.account.oracle.fetch(
+      oracleAddress(poolAddress(withOracle))
+    );
+    if (!pool.hasOracle || !poolOracle.config.equals(poolAddress(withOracle))) {
+      throw new Error("The pool should be created with its oracle");
+    }
+  });
+
+  it("Register the pool in the factory", async () => {
+    // Only the program's upgrade authority may create the factory
+    const programData = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    )[0];
+    const impostor = Keypair.generate();
+    const airdrop = await connection.requestAirdrop(impostor.publicKey, 1e9);
+    await connection.confirmTransaction(airdrop);
+    try {
+      await program.methods
+        .initializeFactory([30, 300])
+        .accounts({
+          authority: impostor.publicKey,
+          programData,
+        })
+        .signers([impostor])
+        .rpc();
+      throw new Error("Only the upgrade authority can create the factory");
+    } catch (err) {
+      if (!err.toString().includes("InvalidAuthority")) throw err;
+    }
+
+    await program.methods
+      .initializeFactory([30, 300])
+      .accounts({
+        authority: payer.publicKey,
+        programData,
+      })
+      .rpc();
+
+    // The registry keys pairs by sorted mints
+    const [mint_a, mint_b] =
+      Buffer.compare(mint_x.toBuffer(), mint_y.toBuffer()) < 0
+        ? [mint_x, mint_y]
+        : [mint_y, mint_x];
+    const pairAddress = (feeTier: number) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("pair"),
+          mint_a.toBuffer(),
+          mint_b.toBuffer(),
+          new anchor.BN(feeTier).toArrayLike(Buffer, "le", 2),
+        ],
+        program.programId
+      )[0];
+
+    try {
+      await program.methods
+        .registerPool(30)
+        .accounts({
+          payer: payer.publicKey,
+          mintA: mint_a,
+          mintB: mint_b,
+          config: config_addr,
+        })
+        .rpc();
+      throw new Error("A pool should only register under its own fee");
+    } catch (err) {
+      if (!err.toString().includes("InvalidFeeTier")) throw err;
+    }
+
+    // Seed 1111 isn't the pair's canonical seed, only the factory authority can register it
+    try {
+      await program.methods
+        .registerPool(300)
+        .accounts({
+          payer: payer.publicKey,
+          mintA: mint_a,
+          mintB: mint_b,
+          config: config_addr,
+        })
+        .rpc();
+      throw new Error("A non canonical pool should need the factory authority");
+    } catch (err) {
+      if (!err.toString().includes("NonCanonicalPool")) throw err;
+    }
+
+    const tx = await program.methods
+      .registerPool(300)
+      .accounts({
+        payer: payer.publicKey,
+        authority: payer.publicKey,
+        mintA: mint_a,
+        mintB: mint_b,
+        config: config_addr,
+      })
+      .rpc();
+    console.log("Pool registered", tx);
+
+    const pair = await program.account.pair.fetch(pairAddress(300));
+    if (!pair.config.equals(config_addr)) {
+      throw new Error("Pair should point at the registered pool");
+    }
+
+    // A pool at the canonical seed registers without the factory authority
+    const hash = createHash("sha256")
+      .update(mint_a.toBuffer())
+      .update(mint_b.toBuffer())
+      .update(new anchor.BN(30).toArrayLike(Buffer, "le", 2))
+      .digest();
+    const canonicalSeed = new anchor.BN(hash.subarray(0, 8), "le");
+    const [canonicalConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config"), canonicalSeed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    await program.methods
+      .initialize(
+        canonicalSeed,
+        30,
+        payer.publicKey,
+        { constantProduct: {} },
+        new anchor.BN(0)
+      )
+      .accounts({
+        initializer: payer.publicKey,
+        mintX: mint_x,
+        mintY: mint_y,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const stranger = Keypair.generate();
+    const sig = await connection.requestAirdrop(stranger.publicKey, 1e9);
+    await connection.confirmTransaction(sig);
+    await program.methods
+      .registerPool(30)
+      .accounts({
+        payer: stranger.publicKey,
+        mintA: mint_a,
+        mintB: mint_b,
+        config: canonicalConfig,
+      })
+      .signers([stranger])
+      .rpc();
+
+    const canonicalPair = await program.account.pair.fetch(pairAddress(30));
+    if (!canonicalPair.config.equals(canonicalConfig)) {
+      throw new Error("Pair should point at the canonical pool");
+    }
+  });
+
   it("Deposit to pool", async () => {
     const tx = await program.methods
       .deposit(new anchor.BN(6000), new anchor.BN(10000), new anchor.BN(50000))
@@ -115,11 +393,23 @@ describe("anchor-amm", () => {
         config: config_addr,
         vaultX: vault_x,
         vaultY: vault_y,
+        oracle,
+        tokenProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
     console.log("Deposit complete", tx);
+    // sqrt(10000 * 50000) = 22360, of which 1000 stays locked in the pool
     let user_lp_mint_ata = await getAccount(connection, payer_lp_ata);
-    console.log("", user_lp_mint_ata.amount);
+    if (user_lp_mint_ata.amount !== BigInt(21360)) {
+      throw new Error("First deposit should mint sqrt(x * y) minus the locked minimum");
+    }
+    const locked_lp = await getAccount(
+      connection,
+      getAssociatedTokenAddressSync(lp_mint, config_addr, true)
+    );
+    if (locked_lp.amount !== BigInt(1000)) {
+      throw new Error("Minimum liquidity should be locked");
+    }
     const get_vault_x = await getAccount(connection, vault_x);
     const get_vault_y = await getAccount(connection, vault_y);
     console.log(
@@ -127,9 +417,20 @@ describe("anchor-amm", () => {
     );
   });
 
+  it("Set protocol fee", async () => {
+    const tx = await program.methods
+      .setProtocolFee(2000)
+      .accounts({
+        authority: payer.publicKey,
+        config: config_addr,
+      })
+      .rpc();
+    console.log("Protocol fee set", tx);
+  });
+
   it("Swap", async () => {
     const tx = await program.methods
-      .swap(true, new anchor.BN(1000), new anchor.BN(4422))
+      .swap(true, new anchor.BN(1000), new anchor.BN(4421))
       .accounts({
         swapper: payer.publicKey,
         mintX: mint_x,
@@ -137,6 +438,7 @@ describe("anchor-amm", () => {
         config: config_addr,
         vaultX: vault_x,
         vaultY: vault_y,
+        oracle,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
@@ -149,9 +451,361 @@ describe("anchor-amm", () => {
     );
   });
 
+  it("Swap exact output", async () => {
+    const before = await getAccount(connection, payer_y_ata);
+    const tx = await program.methods
+      .swapExactOut(true, new anchor.BN(100), new anchor.BN(1000))
+      .accounts({
+        swapper: payer.publicKey,
+        mintX: mint_x,
+        mintY: mint_y,
+        config: config_addr,
+        vaultX: vault_x,
+        vaultY: vault_y,
+        oracle,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    console.log("Swap exact out complete! ", tx);
+    const after = await getAccount(connection, payer_y_ata);
+    if (after.amount - before.amount !== BigInt(100)) {
+      throw new Error("Should receive exactly the requested output");
+    }
+  });
+
+  it("Swap rejects a mint the pool doesn't hold", async () => {
+    // A junk mint with a "vault" owned by the pool config, holding a junk reserve
+    const junk = await createMint(connection, payer, payer.publicKey, null, 6);
+    const junkVault = await createAssociatedTokenAccount(
+      connection,
+      payer,
+      junk,
+      config_addr,
+      undefined,
+      undefined,
+      undefined,
+      true
+    );
+    await mintTo(connection, payer, junk, junkVault, payer, 1);
+    const payerJunk = await createAssociatedTokenAccount(
+      connection,
+      payer,
+      junk,
+      payer.publicKey
+    );
+    await mintTo(connection, payer, junk, payerJunk, payer, 1_000_000_000);
+
+    try {
+      await program.methods
+        .swap(true, new anchor.BN(1_000_000_000), new anchor.BN(0))
+        .accounts({
+          swapper: payer.publicKey,
+          mintX: junk,
+          mintY: mint_y,
+          config: config_addr,
+          vaultX: junkVault,
+          vaultY: vault_y,
+          oracle,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      throw new Error("Swap should reject a mint the pool doesn't hold");
+    } catch (err) {
+      if (!err.toString().includes("InvalidToken")) throw err;
+    }
+  });
+
+  it("Swap emits Swap and Sync events", async () => {
+    const tx = await program.methods
+      .swap(false, new anchor.BN(100), new anchor.BN(1))
+      .accounts({
+        swapper: payer.publicKey,
+        mintX: mint_x,
+        mintY: mint_y,
+        config: config_addr,
+        vaultX: vault_x,
+        vaultY: vault_y,
+        oracle,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc({ commitment: "confirmed" });
+
+    const txDetails = await connection.getTransaction(tx, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    const events = [...parser.parseLogs(txDetails.meta.logMessages)];
+    const swapEvent = events.find((event) => event.name === "swap");
+    const syncEvent = events.find((event) => event.name === "sync");
+    if (!swapEvent || !syncEvent) {
+      throw new Error("Swap should emit Swap and Sync events");
+    }
+
+    // Reserves are the vaults less the uncollected protocol fees
+    const config = await program.account.config.fetch(config_addr);
+    const vaultY = await getAccount(connection, vault_y);
+    const expected = new anchor.BN(vaultY.amount.toString()).sub(
+      config.protocolFeesY
+    );
+    if (
+      swapEvent.data.amountIn.toNumber() !== 100 ||
+      !syncEvent.data.reserveY.eq(expected)
+    ) {
+      throw new Error("Events should carry the amounts and resulting reserves");
+    }
+  });
+
+  it("TWAP rejects windows older than the oracle", async () => {
+    try {
+      await program.methods
+        .getTwap(new anchor.BN(86400))
+        .accounts({
+          mintX: mint_x,
+          mintY: mint_y,
+          config: config_addr,
+          vaultX: vault_x,
+          vaultY: vault_y,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .view();
+      throw new Error("TWAP should fail for a window older than the oracle");
+    } catch (err) {
+      if (!err.toString().includes("OracleWindowTooLong")) throw err;
+    }
+  });
+
+  it("Route swap through the pool", async () => {
+    const tx = await program.methods
+      .routeSwap(new anchor.BN(100), new anchor.BN(1))
+      .accounts({
+        swapper: payer.publicKey,
+        userIn: payer_x_ata,
+        userOut: payer_y_ata,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: config_addr, isSigner: false, isWritable: true },
+        { pubkey: mint_x, isSigner: false, isWritable: false },
+        { pubkey: mint_y, isSigner: false, isWritable: false },
+        { pubkey: vault_x, isSigner: false, isWritable: true },
+        { pubkey: vault_y, isSigner: false, isWritable: true },
+        { pubkey: oracle, isSigner: false, isWritable: true },
+      ])
+      .rpc();
+
+    console.log("Route swap complete! ", tx);
+  });
+
+  it("Swaps must record into the pool's oracle", async () => {
+    try {
+      await program.methods
+        .swap(true, new anchor.BN(100), new anchor.BN(1))
+        .accountsStrict({
+          swapper: payer.publicKey,
+          mintX: mint_x,
+          mintY: mint_y,
+          config: config_addr,
+          vaultX: vault_x,
+          vaultY: vault_y,
+          userX: payer_x_ata,
+          userY: payer_y_ata,
+          oracle: null,
+          ticks: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      throw new Error("Swap should fail without the pool's oracle");
+    } catch (err) {
+      if (!err.toString().includes("MissingOracle")) throw err;
+    }
+  });
+
+  it("Collect protocol fees", async () => {
+    const before = await getAccount(connection, payer_x_ata);
+    const tx = await program.methods
+      .collectProtocolFees()
+      .accounts({
+        authority: payer.publicKey,
+        mintX: mint_x,
+        mintY: mint_y,
+        config: config_addr,
+        vaultX: vault_x,
+        vaultY: vault_y,
+        treasuryX: payer_x_ata,
+        treasuryY: payer_y_ata,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    console.log("Protocol fees collected", tx);
+
+    const after = await getAccount(connection, payer_x_ata);
+    // 2000 bps of the 30 token fee on the 1000 token swap
+    console.log(`Protocol fees received: ${after.amount - before.amount}`);
+  });
+
+  it("Lock blocks swaps until unlocked", async () => {
+    await program.methods
+      .lock()
+      .accounts({
+        authority: payer.publicKey,
+        config: config_addr,
+      })
+      .rpc();
+
+    try {
+      await program.methods
+        .swap(true, new anchor.BN(100), new anchor.BN(1))
+        .accounts({
+          swapper: payer.publicKey,
+          mintX: mint_x,
+          mintY: mint_y,
+          config: config_addr,
+          vaultX: vault_x,
+          vaultY: vault_y,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      throw new Error("Swap should fail while the pool is locked");
+    } catch (err) {
+      if (!err.toString().includes("PoolLocked")) throw err;
+    }
+
+    const tx = await program.methods
+      .unlock()
+      .accounts({
+        authority: payer.publicKey,
+        config: config_addr,
+      })
+      .rpc();
+    console.log("Pool unlocked", tx);
+  });
+
+  it("Amplification ramp is rejected on a constant product pool", async () => {
+    try {
+      await program.methods
+        .rampAmplification(new anchor.BN(200), new anchor.BN(0))
+        .accounts({
+          authority: payer.publicKey,
+          config: config_addr,
+        })
+        .rpc();
+      throw new Error("Ramp should fail on a constant product pool");
+    } catch (err) {
+      if (!err.toString().includes("NotStableSwap")) throw err;
+    }
+  });
+
+  it("Flash loan must be repaid in the same transaction", async () => {
+    const flashAccounts = {
+      borrower: payer.publicKey,
+      mintX: mint_x,
+      mintY: mint_y,
+      config: config_addr,
+      vaultX: vault_x,
+      vaultY: vault_y,
+      borrowerX: payer_x_ata,
+      borrowerY: payer_y_ata,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    try {
+      await program.methods
+        .flashLoan(new anchor.BN(1000), new anchor.BN(0))
+        .accounts(flashAccounts)
+        .rpc();
+      throw new Error("Flash loan without a repay should fail");
+    } catch (err) {
+      if (!err.toString().includes("FlashLoanNotRepaid")) throw err;
+    }
+
+    const before = await getAccount(connection, vault_x);
+    const loanIx = await program.methods
+      .flashLoan(new anchor.BN(1000), new anchor.BN(0))
+      .accounts(flashAccounts)
+      .instruction();
+    const repayIx = await program.methods
+      .flashRepay()
+      .accounts(flashAccounts)
+      .instruction();
+    const tx = await sendAndConfirmTransaction(
+      connection,
+      new Transaction().add(loanIx, repayIx),
+      [payer]
+    );
+    console.log("Flash loan repaid", tx);
+
+    // 1000 X at the 3% pool fee
+    const after = await getAccount(connection, vault_x);
+    if (after.amount - before.amount !== BigInt(30)) {
+      throw new Error("Vault should keep the flash loan fee");
+    }
+  });
+
+  it("Zap in and out with a single token", async () => {
+    const zapAccounts = {
+      user: payer.publicKey,
+      mintX: mint_x,
+      mintY: mint_y,
+      config: config_addr,
+      mintLp: lp_mint,
+      vaultX: vault_x,
+      vaultY: vault_y,
+      oracle,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    const lpBefore = (await getAccount(connection, payer_lp_ata)).amount;
+    await program.methods
+      .depositSingle(true, new anchor.BN(1000), new anchor.BN(1))
+      .accounts(zapAccounts)
+      .rpc();
+    const lpMinted = (await getAccount(connection, payer_lp_ata)).amount - lpBefore;
+    console.log(`Zap minted ${lpMinted} LP for 1000 X`);
+
+    const xBefore = (await getAccount(connection, payer_x_ata)).amount;
+    await program.methods
+      .withdrawSingle(new anchor.BN(lpMinted.toString()), true, new anchor.BN(1))
+      .accounts(zapAccounts)
+      .rpc();
+    const xReturned = (await getAccount(connection, payer_x_ata)).amount - xBefore;
+    console.log(`Zap out returned ${xReturned} X`);
+
+    // Round trip pays the swap fee twice on the swapped part
+    if (xReturned >= BigInt(1000) || xReturned < BigInt(990)) {
+      throw new Error("Single-sided round trip should only lose fees");
+    }
+  });
+
+  it("Withdraw rejects the LP mint as mint X", async () => {
+    // Its vault would be the pool's locked LP, paid out as if it were reserve
+    const lockedLp = getAssociatedTokenAddressSync(lp_mint, config_addr, true);
+    try {
+      await program.methods
+        .withdraw(new anchor.BN(1000), new anchor.BN(0), new anchor.BN(0))
+        .accounts({
+          withdrawer: payer.publicKey,
+          mintX: lp_mint,
+          mintY: mint_y,
+          config: config_addr,
+          vaultX: lockedLp,
+          vaultY: vault_y,
+          oracle,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      throw new Error("Withdraw should reject a mint the pool doesn't hold");
+    } catch (err) {
+      if (!err.toString().includes("InvalidToken")) throw err;
+    }
+  });
+
   it("Withdraw complete !", async () => {
     const tx = await program.methods
-      .withdraw(new anchor.BN(5000), new anchor.BN(9166), new anchor.BN(37981))
+      .withdraw(new anchor.BN(18000), new anchor.BN(8000), new anchor.BN(30000))
       .accounts({
         withdrawer: payer.publicKey,
         mintX: mint_x,
@@ -159,6 +813,7 @@ describe("anchor-amm", () => {
         config: config_addr,
         vaultX: vault_x,
         vaultY: vault_y,
+        oracle,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
       .rpc();
@@ -170,4 +825,525 @@ describe("anchor-amm", () => {
       `Vault X : ${get_vault_x.amount}, Vault Y: ${get_vault_y.amount}`
     );
   });
+
+  describe("StableSwap pool", () => {
+    const stableSeed = new anchor.BN(2222);
+    let stableConfig: PublicKey;
+    let stableLp: PublicKey;
+    let stableVaultX: PublicKey;
+    let stableVaultY: PublicKey;
+
+    before(() => {
+      [stableConfig] = PublicKey.findProgramAddressSync(
+        [Buffer.from("config"), stableSeed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      [stableLp] = PublicKey.findProgramAddressSync(
+        [Buffer.from("lp"), stableConfig.toBuffer()],
+        program.programId
+      );
+      stableVaultX = getAssociatedTokenAddressSync(mint_x, stableConfig, true);
+      stableVaultY = getAssociatedTokenAddressSync(mint_y, stableConfig, true);
+    });
+
+    it("Initialize and reject a dust first deposit", async () => {
+      await program.methods
+        .initialize(
+          stableSeed,
+          30,
+          payer.publicKey,
+          { stableSwap: {} },
+          new anchor.BN(100)
+        )
+        .accounts({
+          initializer: payer.publicKey,
+          mintX: mint_x,
+          mintY: mint_y,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+      try {
+        await program.methods
+          .deposit(new anchor.BN(1), new anchor.BN(30), new anchor.BN(30))
+          .accounts({
+            user: payer.publicKey,
+            mintX: mint_x,
+            mintY: mint_y,
+            mintLp: stableLp,
+            config: stableConfig,
+            vaultX: stableVaultX,
+            vaultY: stableVaultY,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc();
+        throw new Error("First deposit below the minimum liquidity should fail");
+      } catch (err) {
+        if (!err.toString().includes("LiquidityLessThanMinimum")) throw err;
+      }
+
+      const tx = await program.methods
+        .deposit(
+          new anchor.BN(9000),
+          new anchor.BN(10000),
+          new anchor.BN(10000)
+        )
+        .accounts({
+          user: payer.publicKey,
+          mintX: mint_x,
+          mintY: mint_y,
+          mintLp: stableLp,
+          config: stableConfig,
+          vaultX: stableVaultX,
+          vaultY: stableVaultY,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      console.log("Stable pool deposit complete", tx);
+    });
+
+    it("Swaps close to parity", async () => {
+      const before = await getAccount(connection, payer_y_ata);
+      await program.methods
+        .swap(true, new anchor.BN(1000), new anchor.BN(1))
+        .accounts({
+          swapper: payer.publicKey,
+          mintX: mint_x,
+          mintY: mint_y,
+          config: stableConfig,
+          vaultX: stableVaultX,
+          vaultY: stableVaultY,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      const after = await getAccount(connection, payer_y_ata);
+
+      // Constant product would only give ~906 here
+      const received = after.amount - before.amount;
+      console.log(`Stable swap received: ${received}`);
+      if (received < BigInt(990)) {
+        throw new Error("StableSwap output should stay close to parity");
+      }
+    });
+
+    it("Ramp rejects a stop time too soon", async () => {
+      try {
+        await program.methods
+          .rampAmplification(new anchor.BN(200), new anchor.BN(0))
+          .accounts({
+            authority: payer.publicKey,
+            config: stableConfig,
+          })
+          .rpc();
+        throw new Error("Ramp should fail without a minimum duration");
+      } catch (err) {
+        if (!err.toString().includes("InvalidRamp")) throw err;
+      }
+    });
+
+    it("Dynamic fee prices in the swap's own move and leaves the static fee alone", async () => {
+      await program.methods
+        .enableDynamicFee(50, 500, 65535, new anchor.BN(3600))
+        .accounts({
+          authority: payer.publicKey,
+          config: stableConfig,
+        })
+        .rpc();
+
+      const swapFee = async (amount: number) => {
+        const tx = await program.methods
+          .swap(true, new anchor.BN(amount), new anchor.BN(1))
+          .accounts({
+            swapper: payer.publicKey,
+            mintX: mint_x,
+            mintY: mint_y,
+            config: stableConfig,
+            vaultX: stableVaultX,
+            vaultY: stableVaultY,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc({ commitment: "confirmed" });
+        const txDetails = await connection.getTransaction(tx, {
+          commitment: "confirmed",
+          maxSupportedTransactionVersion: 0,
+        });
+        const parser = new anchor.EventParser(program.programId, program.coder);
+        const swapEvent = [...parser.parseLogs(txDetails.meta.logMessages)].find(
+          (event) => event.name === "swap"
+        );
+        return swapEvent.data.fee.toNumber();
+      };
+
+      // The first swap sets the reference price, but its own move is still charged for
+      const fee = await swapFee(5000);
+      console.log(`Dynamic fee on the first swap: ${fee}`);
+      if (fee <= (5000 * 50) / 10000) {
+        throw new Error(`Expected more than the base fee, got ${fee}`);
+      }
+
+      let config = await program.account.config.fetch(stableConfig);
+      if (config.fee !== 30) {
+        throw new Error("Swaps should not overwrite the static fee");
+      }
+
+      await program.methods
+        .disableDynamicFee()
+        .accounts({
+          authority: payer.publicKey,
+          config: stableConfig,
+        })
+        .rpc();
+      config = await program.account.config.fetch(stableConfig);
+      if (config.fee !== 30 || config.dynamicFee.enabled) {
+        throw new Error("Disabling should go back to the static fee");
+      }
+    });
+  });
+
+  describe("Concentrated pool", () => {
+    const clSeed = new anchor.BN(3333);
+    const tickLower = -100;
+    const tickUpper = 100;
+    const liquidity = new anchor.BN(1_000_000);
+    let clConfig: PublicKey;
+    let clVaultX: PublicKey;
+    let clVaultY: PublicKey;
+    let ticks: PublicKey;
+    let position: PublicKey;
+
+    const tickBytes = (tick: number) => {
+      const buf = Buffer.alloc(4);
+      buf.writeInt32LE(tick);
+      return buf;
+    };
+
+    before(() => {
+      [clConfig] = PublicKey.findProgramAddressSync(
+        [Buffer.from("config"), clSeed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      [ticks] = PublicKey.findProgramAddressSync(
+        [Buffer.from("ticks"), clConfig.toBuffer()],
+        program.programId
+      );
+      [position] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("position"),
+          clConfig.toBuffer(),
+          payer.publicKey.toBuffer(),
+          tickBytes(tickLower),
+          tickBytes(tickUpper),
+        ],
+        program.programId
+      );
+      clVaultX = getAssociatedTokenAddressSync(mint_x, clConfig, true);
+      clVaultY = getAssociatedTokenAddressSync(mint_y, clConfig, true);
+    });
+
+    const modifyAccounts = () => ({
+      owner: payer.publicKey,
+      mintX: mint_x,
+      mintY: mint_y,
+      config: clConfig,
+      ticks,
+      position,
+      vaultX: clVaultX,
+      vaultY: clVaultY,
+      userX: payer_x_ata,
+      userY: payer_y_ata,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+    it("Initialize at a price of 1", async () => {
+      await program.methods
+        .initialize(
+          clSeed,
+          30,
+          payer.publicKey,
+          { concentrated: {} },
+          new anchor.BN(0)
+        )
+        .accounts({
+          initializer: payer.publicKey,
+          mintX: mint_x,
+          mintY: mint_y,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+      // Q64.64 sqrt price of 1
+      const tx = await program.methods
+        .initializeConcentrated(10, new anchor.BN(1).shln(64))
+        .accounts({
+          payer: payer.publicKey,
+          config: clConfig,
+        })
+        .rpc();
+      console.log("Concentrated pool initialized", tx);
+    });
+
+    it("Open a position and add liquidity", async () => {
+      await program.methods
+        .openPosition(tickLower, tickUpper)
+        .accounts({
+          owner: payer.publicKey,
+          config: clConfig,
+        })
+        .rpc();
+
+      await program.methods
+        .increaseLiquidity(liquidity, new anchor.BN(6000), new anchor.BN(6000))
+        .accounts(modifyAccounts())
+        .rpc();
+
+      const config = await program.account.config.fetch(clConfig);
+      if (!config.liquidity.eq(liquidity)) {
+        throw new Error("In range liquidity should be active");
+      }
+    });
+
+    it("Swaps within the range", async () => {
+      const before = await getAccount(connection, payer_y_ata);
+      await program.methods
+        .swap(true, new anchor.BN(1000), new anchor.BN(900))
+        .accounts({
+          swapper: payer.publicKey,
+          mintX: mint_x,
+          mintY: mint_y,
+          config: clConfig,
+          vaultX: clVaultX,
+          vaultY: clVaultY,
+          ticks,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      const after = await getAccount(connection, payer_y_ata);
+      console.log(`Concentrated swap received: ${after.amount - before.amount}`);
+    });
+
+    it("Collect fees, remove liquidity and close", async () => {
+      await program.methods.collectFees().accounts(modifyAccounts()).rpc();
+
+      let state = await program.account.position.fetch(position);
+      if (state.feesOwedX.toNumber() !== 0) {
+        throw new Error("Collected fees should be cleared");
+      }
+
+      await program.methods
+        .decreaseLiquidity(liquidity, new anchor.BN(0), new anchor.BN(0))
+        .accounts(modifyAccounts())
+        .rpc();
+      await program.methods
+        .closePosition()
+        .accounts({ owner: payer.publicKey, position })
+        .rpc();
+
+      const tickList = await program.account.tickList.fetch(ticks);
+      if (tickList.ticks.length !== 0) {
+        throw new Error("Emptied ticks should be removed");
+      }
+    });
+  });
+
+  describe("Token-2022 pool with transfer fees", () => {
+    const feeSeed = new anchor.BN(4444);
+    let feeMintX: PublicKey;
+    let feeMintY: PublicKey;
+    let feeConfig: PublicKey;
+    let feeVaultX: PublicKey;
+    let feeLp: PublicKey;
+    let userFeeX: PublicKey;
+    let userFeeY: PublicKey;
+
+    // 1% transfer fee on every transfer
+    const createFeeMint = async () => {
+      const mint = Keypair.generate();
+      const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+      const lamports = await connection.getMinimumBalanceForRentExemption(
+        mintLen
+      );
+      const tx = new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: payer.publicKey,
+          newAccountPubkey: mint.publicKey,
+          space: mintLen,
+          lamports,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          mint.publicKey,
+          payer.publicKey,
+          payer.publicKey,
+          100,
+          BigInt(1_000_000),
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(
+          mint.publicKey,
+          6,
+          payer.publicKey,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        )
+      );
+      await sendAndConfirmTransaction(connection, tx, [payer, mint]);
+      return mint.publicKey;
+    };
+
+    before(async () => {
+      feeMintX = await createFeeMint();
+      feeMintY = await createFeeMint();
+
+      userFeeX = await createAssociatedTokenAccount(
+        connection,
+        payer,
+        feeMintX,
+        payer.publicKey,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      userFeeY = await createAssociatedTokenAccount(
+        connection,
+        payer,
+        feeMintY,
+        payer.publicKey,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(
+        connection,
+        payer,
+        feeMintX,
+        userFeeX,
+        payer,
+        100000,
+        [],
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      await mintTo(
+        connection,
+        payer,
+        feeMintY,
+        userFeeY,
+        payer,
+        100000,
+        [],
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      [feeConfig] = PublicKey.findProgramAddressSync(
+        [Buffer.from("config"), feeSeed.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      [feeLp] = PublicKey.findProgramAddressSync(
+        [Buffer.from("lp"), feeConfig.toBuffer()],
+        program.programId
+      );
+      feeVaultX = getAssociatedTokenAddressSync(
+        feeMintX,
+        feeConfig,
+        true,
+        TOKEN_2022_PROGRAM_ID
+      );
+    });
+
+    it("Initialize rejects a pair mixing Token and Token-2022", async () => {
+      try {
+        await program.methods
+          .initialize(
+            new anchor.BN(4445),
+            30,
+            payer.publicKey,
+            { constantProduct: {} },
+            new anchor.BN(0)
+          )
+          .accounts({
+            initializer: payer.publicKey,
+            mintX: mint_x,
+            mintY: feeMintY,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .rpc();
+        throw new Error("A mixed pair should be rejected");
+      } catch (err) {
+        if (!err.toString().includes("MixedTokenPrograms")) throw err;
+      }
+    });
+
+    it("Initialize and fund the pool", async () => {
+      await program.methods
+        .initialize(
+          feeSeed,
+          30,
+          payer.publicKey,
+          { constantProduct: {} },
+          new anchor.BN(0)
+        )
+        .accounts({
+          initializer: payer.publicKey,
+          mintX: feeMintX,
+          mintY: feeMintY,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+
+      await program.methods
+        .deposit(new anchor.BN(1), new anchor.BN(10000), new anchor.BN(10000))
+        .accounts({
+          user: payer.publicKey,
+          mintX: feeMintX,
+          mintY: feeMintY,
+          mintLp: feeLp,
+          config: feeConfig,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+
+      // The vaults only received 9900 of each side
+      const userLp = await getAccount(
+        connection,
+        getAssociatedTokenAddressSync(
+          feeLp,
+          payer.publicKey,
+          false,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      if (userLp.amount !== BigInt(8900)) {
+        throw new Error("LP should be priced on the amounts after fees");
+      }
+    });
+
+    it("Swap prices the input after the transfer fee", async () => {
+      const before = await getAccount(
+        connection,
+        feeVaultX,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      await program.methods
+        .swap(true, new anchor.BN(1000), new anchor.BN(1))
+        .accounts({
+          swapper: payer.publicKey,
+          mintX: feeMintX,
+          mintY: feeMintY,
+          config: feeConfig,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      const after = await getAccount(
+        connection,
+        feeVaultX,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      if (after.amount - before.amount !== BigInt(990)) {
+        throw new Error("Vault should receive the input minus the 1% fee");
+      }
+    });
+  });
 });